@@ -1,5 +1,7 @@
+use bump_bin::version::{Part, PrePolicy, DEFAULT_PRE_ID};
 use clap::builder::{styling, Styles};
 use clap::{Parser, Subcommand};
+use std::cmp::Ordering;
 
 fn help_styles() -> Styles {
     styling::Styles::styled()
@@ -16,40 +18,220 @@ pub struct Args {
     pub(crate) sub: SubCommand,
 }
 
+#[derive(clap::Args)]
+pub struct Input {
+    /// Semver file
+    #[arg(short, long)]
+    pub(crate) file: Option<String>,
+
+    /// Write the bumped version back to the file, or to the files of the config
+    #[arg(short, long, conflicts_with_all = ["SEMANTIC_VER", "from_git"])]
+    pub(crate) write: bool,
+
+    /// Regex locating the version in the file with a capture group named `version`
+    #[arg(short, long, requires = "file")]
+    pub(crate) pattern: Option<String>,
+
+    /// Print a diff of the files instead of writing them, exit with 1 if they would change
+    #[arg(short = 'n', long)]
+    pub(crate) dry_run: bool,
+
+    /// Read the version from the highest version tag of the git repository
+    #[arg(short = 'g', long, conflicts_with_all = ["file", "config", "SEMANTIC_VER"])]
+    pub(crate) from_git: bool,
+
+    /// Add a section for the bumped version to a Keep a Changelog file, from Conventional Commits
+    #[arg(long, value_name = "PATH")]
+    pub(crate) changelog: Option<String>,
+
+    /// Create an annotated git tag named after the bumped version
+    #[arg(short, long)]
+    pub(crate) tag: bool,
+
+    /// Message of the git tag [default: the tag name]
+    #[arg(long, requires = "tag")]
+    pub(crate) tag_message: Option<String>,
+
+    /// Config file listing every file to rewrite [default: .bump.toml if present]
+    #[arg(short, long, conflicts_with_all = ["file", "SEMANTIC_VER"])]
+    pub(crate) config: Option<String>,
+
+    /// Output format, json prints every part of the bumped version and the previous one
+    #[arg(long, value_enum, default_value_t)]
+    pub(crate) output: OutputFormat,
+
+    /// Print the bumped version through a template like {major}.{minor} or {prefix}{major}
+    #[arg(long, value_name = "TEMPLATE", conflicts_with = "output")]
+    pub(crate) format: Option<String>,
+
+    /// Replace the prefix of the printed and tagged version, like v in v1.0.0, files keep their own
+    #[arg(long, conflicts_with_all = ["write", "dry_run"])]
+    pub(crate) prefix: Option<String>,
+
+    /// Remove the prefix of the printed and tagged version, files keep their own
+    #[arg(long, conflicts_with_all = ["prefix", "write", "dry_run"])]
+    pub(crate) strip_prefix: bool,
+
+    /// Comma separated prefixes the current version may have, rejecting any other
+    #[arg(long, value_name = "PREFIXES", value_delimiter = ',')]
+    pub(crate) allowed_prefixes: Vec<String>,
+
+    /// Comma separated suffixes to keep as is, like -linux-amd64 that would read as a pre-release
+    #[arg(
+        long,
+        value_name = "SUFFIXES",
+        value_delimiter = ',',
+        allow_hyphen_values = true
+    )]
+    pub(crate) known_suffixes: Vec<String>,
+
+    /// Bump every version read from stdin, one per line, printing a result per line
+    #[arg(long, conflicts_with_all = ["file", "config", "from_git", "SEMANTIC_VER", "write", "dry_run", "tag", "changelog"])]
+    pub(crate) lines: bool,
+
+    /// Carry on past the lines that fail with --lines, reporting them to stderr
+    #[arg(long, requires = "lines")]
+    pub(crate) keep_going: bool,
+}
+
 #[derive(Subcommand)]
 pub enum SubCommand {
     /// Increment patch version
     Patch {
-        /// Semver file
-        #[arg(short, long)]
-        file: Option<String>,
+        #[command(flatten)]
+        input: Input,
+
+        /// How to bump a version that already is a pre-release
+        #[arg(long, value_enum, default_value_t)]
+        pre_policy: PrePolicyArg,
 
         #[arg(name = "SEMANTIC_VER")]
         ver: Option<String>,
     },
     /// Increment minor version
     Minor {
-        /// Semver file
-        #[arg(short, long)]
-        file: Option<String>,
+        #[command(flatten)]
+        input: Input,
+
+        /// How to bump a version that already is a pre-release
+        #[arg(long, value_enum, default_value_t)]
+        pre_policy: PrePolicyArg,
 
         #[arg(name = "SEMANTIC_VER")]
         ver: Option<String>,
     },
     /// Increment major version
     Major {
-        /// Semver file
+        #[command(flatten)]
+        input: Input,
+
+        /// How to bump a version that already is a pre-release
+        #[arg(long, value_enum, default_value_t)]
+        pre_policy: PrePolicyArg,
+
+        #[arg(name = "SEMANTIC_VER")]
+        ver: Option<String>,
+    },
+    /// Increment the version from Conventional Commits since the latest version tag
+    Auto {
+        #[command(flatten)]
+        input: Input,
+
+        /// Print the chosen level and the commit that decided it to stderr
         #[arg(short, long)]
-        file: Option<String>,
+        explain: bool,
 
+        /// How to bump a version that already is a pre-release
+        #[arg(long, value_enum, default_value_t)]
+        pre_policy: PrePolicyArg,
+
+        /// Current version [default: the latest version tag]
+        #[arg(name = "SEMANTIC_VER")]
+        ver: Option<String>,
+    },
+    /// Increment patch version and start a pre-release of it
+    Prepatch {
+        #[command(flatten)]
+        input: Input,
+
+        /// Pre-release identifier
+        #[arg(long, default_value = DEFAULT_PRE_ID)]
+        preid: String,
+
+        #[arg(name = "SEMANTIC_VER")]
+        ver: Option<String>,
+    },
+    /// Increment minor version and start a pre-release of it
+    Preminor {
+        #[command(flatten)]
+        input: Input,
+
+        /// Pre-release identifier
+        #[arg(long, default_value = DEFAULT_PRE_ID)]
+        preid: String,
+
+        #[arg(name = "SEMANTIC_VER")]
+        ver: Option<String>,
+    },
+    /// Increment major version and start a pre-release of it
+    Premajor {
+        #[command(flatten)]
+        input: Input,
+
+        /// Pre-release identifier
+        #[arg(long, default_value = DEFAULT_PRE_ID)]
+        preid: String,
+
+        #[arg(name = "SEMANTIC_VER")]
+        ver: Option<String>,
+    },
+    /// Increment pre-release counter, or prepatch if there is no pre-release
+    Prerelease {
+        #[command(flatten)]
+        input: Input,
+
+        /// Pre-release identifier [default: the current one, or rc]
+        #[arg(long)]
+        preid: Option<String>,
+
+        #[arg(name = "SEMANTIC_VER")]
+        ver: Option<String>,
+    },
+    /// Remove pre-release version, like 2.0.0-rc.4 to 2.0.0
+    Release {
+        #[command(flatten)]
+        input: Input,
+
+        /// Remove build metadata as well
+        #[arg(long)]
+        strip_build: bool,
+
+        #[arg(name = "SEMANTIC_VER")]
+        ver: Option<String>,
+    },
+    /// Set a specific version, refusing to go backwards
+    Set {
+        #[command(flatten)]
+        input: Input,
+
+        /// Allow a version lower than the current one
+        #[arg(long)]
+        allow_downgrade: bool,
+
+        #[arg(name = "NEW_VER")]
+        new: String,
+        /// Current version
         #[arg(name = "SEMANTIC_VER")]
         ver: Option<String>,
     },
     /// Replace pre-release version
     Pre {
-        /// Semver file
-        #[arg(short, long)]
-        file: Option<String>,
+        #[command(flatten)]
+        input: Input,
+
+        /// Increment the counter of the PRERELEASE identifier instead, like rc.3 to rc.4
+        #[arg(long)]
+        next: bool,
 
         #[arg(name = "PRERELEASE")]
         pre: String,
@@ -58,15 +240,176 @@ pub enum SubCommand {
     },
     /// Replace build metadata
     Build {
-        /// Semver file
-        #[arg(short, long)]
-        file: Option<String>,
+        #[command(flatten)]
+        input: Input,
 
         #[arg(name = "BUILD")]
         build: String,
         #[arg(name = "SEMANTIC_VER")]
         ver: Option<String>,
     },
+    /// Compare two versions, printing <, = or >
+    Compare {
+        /// Exit with 1 unless the first version relates to the second this way
+        #[arg(long, value_enum)]
+        expect: Option<Relation>,
+
+        #[arg(name = "VER_A")]
+        a: String,
+        #[arg(name = "VER_B")]
+        b: String,
+    },
+    /// Sort the versions read from stdin, one per line
+    Sort {
+        /// Sort from the highest to the lowest
+        #[arg(short, long)]
+        reverse: bool,
+    },
+    /// Check whether a version satisfies a requirement like ^1.2 or ">=1.4, <2"
+    Satisfies {
+        /// Print the highest of the versions read from stdin that satisfies REQ instead
+        #[arg(long, conflicts_with = "SEMANTIC_VER")]
+        max_satisfying: bool,
+
+        #[arg(name = "REQ")]
+        req: String,
+        #[arg(name = "SEMANTIC_VER")]
+        ver: Option<String>,
+    },
+    /// Check that a version follows Semantic Versioning, explaining why not otherwise
+    Validate {
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        output: OutputFormat,
+
+        #[arg(name = "SEMANTIC_VER")]
+        ver: String,
+    },
+    /// Print one part of a version
+    Get {
+        /// Semver file
+        #[arg(short, long, conflicts_with = "SEMANTIC_VER")]
+        file: Option<String>,
+
+        #[arg(name = "PART", value_enum)]
+        part: PartArg,
+        #[arg(name = "SEMANTIC_VER")]
+        ver: Option<String>,
+    },
+}
+
+/// How bumping treats a version that already is a pre-release, see [`PrePolicy`]
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+pub enum PrePolicyArg {
+    /// Always increment, `1.1.0-rc.1` becomes `1.2.0` for minor
+    #[default]
+    Increment,
+    /// Release a pre-release of the bumped version as npm does, `1.1.0-rc.1` becomes `1.1.0` for
+    /// minor, while `1.1.1-rc.1` still becomes `1.2.0`
+    Finalize,
+}
+
+impl From<PrePolicyArg> for PrePolicy {
+    fn from(policy: PrePolicyArg) -> Self {
+        match policy {
+            PrePolicyArg::Increment => PrePolicy::Increment,
+            PrePolicyArg::Finalize => PrePolicy::Finalize,
+        }
+    }
+}
+
+/// One of the parts a version is made of, see [`Part`]
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum PartArg {
+    Prefix,
+    Major,
+    Minor,
+    Patch,
+    Pre,
+    Build,
+    Suffix,
+}
+
+impl From<PartArg> for Part {
+    fn from(part: PartArg) -> Self {
+        match part {
+            PartArg::Prefix => Part::Prefix,
+            PartArg::Major => Part::Major,
+            PartArg::Minor => Part::Minor,
+            PartArg::Patch => Part::Patch,
+            PartArg::Pre => Part::Pre,
+            PartArg::Build => Part::Build,
+            PartArg::Suffix => Part::Suffix,
+        }
+    }
+}
+
+/// How to print the result
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// Expected result of a comparison
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum Relation {
+    Lt,
+    Le,
+    Eq,
+    Ne,
+    Ge,
+    Gt,
+}
+
+impl Relation {
+    pub(crate) fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Relation::Lt => ordering.is_lt(),
+            Relation::Le => ordering.is_le(),
+            Relation::Eq => ordering.is_eq(),
+            Relation::Ne => ordering.is_ne(),
+            Relation::Ge => ordering.is_ge(),
+            Relation::Gt => ordering.is_gt(),
+        }
+    }
+}
+
+impl Input {
+    /// Returns the prefix to use in place of `prefix`, as --prefix and --strip-prefix ask.
+    pub(crate) fn prefix<'a>(&'a self, prefix: &'a str) -> &'a str {
+        match (&self.prefix, self.strip_prefix) {
+            (Some(prefix), _) => prefix,
+            (None, true) => "",
+            (None, false) => prefix,
+        }
+    }
+}
+
+impl SubCommand {
+    /// Returns the input of the subcommands that bump a version, `None` for the others.
+    pub(crate) fn input(&self) -> Option<(&Input, Option<&String>)> {
+        match self {
+            SubCommand::Patch { input, ver, .. }
+            | SubCommand::Minor { input, ver, .. }
+            | SubCommand::Major { input, ver, .. }
+            | SubCommand::Auto { input, ver, .. }
+            | SubCommand::Prepatch { input, ver, .. }
+            | SubCommand::Preminor { input, ver, .. }
+            | SubCommand::Premajor { input, ver, .. }
+            | SubCommand::Prerelease { input, ver, .. }
+            | SubCommand::Release { input, ver, .. }
+            | SubCommand::Set { input, ver, .. }
+            | SubCommand::Pre { input, ver, .. }
+            | SubCommand::Build { input, ver, .. } => Some((input, ver.as_ref())),
+            SubCommand::Compare { .. }
+            | SubCommand::Sort { .. }
+            | SubCommand::Satisfies { .. }
+            | SubCommand::Validate { .. }
+            | SubCommand::Get { .. } => None,
+        }
+    }
 }
 
 #[test]
@@ -0,0 +1,169 @@
+use crate::conventional::Message;
+use crate::git::Commit;
+use crate::version::Version;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+static UNRELEASED: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?mi)^##[ \t]+\[?unreleased\]?.*$").unwrap());
+
+static RELEASE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?m)^##[ \t]").unwrap());
+
+const EMPTY: &str = "# Changelog\n\n## [Unreleased]\n";
+
+/// Headings of the groups in the order they appear.
+const GROUPS: [&str; 4] = [
+    "Breaking Changes",
+    "Features",
+    "Bug Fixes",
+    "Performance Improvements",
+];
+
+/// Returns the heading of the group a commit belongs to, if it is worth listing.
+fn group(message: &Message) -> Option<&'static str> {
+    match message.kind.as_str() {
+        _ if message.breaking => Some(GROUPS[0]),
+        "feat" => Some(GROUPS[1]),
+        "fix" => Some(GROUPS[2]),
+        "perf" => Some(GROUPS[3]),
+        _ => None,
+    }
+}
+
+/// Renders the section of a release in [Keep a Changelog] format, grouping `commits` (newest first,
+/// as git lists them) by their Conventional Commits type.
+///
+/// [Keep a Changelog]: https://keepachangelog.com/
+pub fn section(version: &Version, date: &str, commits: &[Commit]) -> String {
+    let mut section = format!("## [{}] - {date}\n", version.semver());
+
+    let messages = commits
+        .iter()
+        .rev()
+        .filter_map(|commit| Message::parse(&commit.message).map(|m| (m, commit)))
+        .collect::<Vec<_>>();
+    for heading in GROUPS {
+        let entries = messages
+            .iter()
+            .filter(|(m, _)| group(m) == Some(heading))
+            .map(|(m, commit)| match m.scope {
+                Some(scope) => format!("- **{scope}:** {} ({})\n", m.description, commit.hash),
+                None => format!("- {} ({})\n", m.description, commit.hash),
+            })
+            .collect::<String>();
+        if !entries.is_empty() {
+            section.push_str(&format!("\n### {heading}\n\n{entries}"));
+        }
+    }
+    section
+}
+
+/// Inserts `section` right under the Unreleased heading, or above the latest release if there is
+/// no such heading.
+///
+/// Entries already listed under Unreleased end up in the new release.
+pub fn insert(changelog: &str, section: &str) -> String {
+    let changelog = if changelog.trim().is_empty() {
+        EMPTY
+    } else {
+        changelog
+    };
+
+    if let Some(m) = UNRELEASED.find(changelog) {
+        let (head, tail) = changelog.split_at(m.end());
+        let tail = tail.trim_start_matches(['\r', '\n']);
+        if tail.is_empty() {
+            return format!("{head}\n\n{section}");
+        }
+        return format!("{head}\n\n{section}\n{tail}");
+    }
+    if let Some(m) = RELEASE.find(changelog) {
+        let (head, tail) = changelog.split_at(m.start());
+        return format!("{head}{section}\n{tail}");
+    }
+    format!("{}\n\n{section}", changelog.trim_end())
+}
+
+/// Returns the current date in UTC as `YYYY-MM-DD`.
+pub fn today() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let (y, m, d) = civil_from_days((secs / 86_400) as i64);
+    format!("{y:04}-{m:02}-{d:02}")
+}
+
+/// Converts days since 1970-01-01 into a proleptic Gregorian date.
+///
+/// See <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    (y, m, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conventional::tests::commits;
+    use std::str::FromStr;
+
+    #[test]
+    fn section_ok() {
+        let version = Version::from_str("v1.1.0").unwrap();
+        let log = commits(&[
+            "fix(cli): typo",
+            "docs: readme",
+            "feat!: drop --old",
+            "feat: add auto",
+            "not conventional",
+        ]);
+        let expect = "## [1.1.0] - 2026-10-18
+
+### Breaking Changes
+
+- drop --old (c2)
+
+### Features
+
+- add auto (c3)
+
+### Bug Fixes
+
+- **cli:** typo (c0)
+";
+        assert_eq!(expect, section(&version, "2026-10-18", &log));
+    }
+
+    #[test]
+    fn insert_ok() {
+        let section = "## [1.1.0] - 2026-10-18\n\n### Features\n\n- add auto (c3)\n";
+
+        let changelog = "# Changelog\n\n## [Unreleased]\n\n## [1.0.0] - 2026-01-01\n\n- first\n";
+        let expect = "# Changelog\n\n## [Unreleased]\n\n## [1.1.0] - 2026-10-18\n\n### Features\n\n- add auto (c3)\n\n## [1.0.0] - 2026-01-01\n\n- first\n";
+        assert_eq!(expect, insert(changelog, section));
+
+        let changelog = "# Changelog\n\n## [1.0.0] - 2026-01-01\n";
+        let expect = "# Changelog\n\n## [1.1.0] - 2026-10-18\n\n### Features\n\n- add auto (c3)\n\n## [1.0.0] - 2026-01-01\n";
+        assert_eq!(expect, insert(changelog, section));
+
+        let expect = "# Changelog\n\n## [Unreleased]\n\n## [1.1.0] - 2026-10-18\n\n### Features\n\n- add auto (c3)\n";
+        assert_eq!(expect, insert("", section));
+    }
+
+    #[test]
+    fn civil_from_days_ok() {
+        assert_eq!((1970, 1, 1), civil_from_days(0));
+        assert_eq!((2000, 2, 29), civil_from_days(11_016));
+        assert_eq!((2026, 10, 18), civil_from_days(20_744));
+    }
+}
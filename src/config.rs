@@ -0,0 +1,226 @@
+use crate::error::Error;
+use crate::file::VersionFile;
+use crate::fs::read_file;
+use crate::source::{detect, Json, Pattern, Toml, VersionSource, Yaml};
+use crate::version::Version;
+use anyhow::{anyhow, bail, Result};
+
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILE: &str = ".bump.toml";
+
+/// Project configuration listing every file that carries the version.
+///
+/// ```toml
+/// [[file]]
+/// path = "Cargo.toml"
+///
+/// [[file]]
+/// path = "charts/app/Chart.yaml"
+/// key = "appVersion"
+///
+/// [[file]]
+/// path = "README.md"
+/// pattern = 'badge/version-(?P<version>[^-]+)-blue'
+/// ```
+///
+/// The first file holds the canonical version.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(rename = "file", default)]
+    pub files: Vec<FileConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileConfig {
+    /// Relative to the directory of the configuration file
+    pub path: PathBuf,
+    /// Regex with a capture group named `version`
+    pub pattern: Option<String>,
+    /// Dotted key of a TOML or JSON file, or a top-level key of a YAML file
+    pub key: Option<String>,
+}
+
+impl Config {
+    /// Returns the configuration file of the current directory, if any.
+    pub fn discover() -> Option<PathBuf> {
+        let path = PathBuf::from(CONFIG_FILE);
+        path.is_file().then_some(path)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let buf = String::from_utf8(read_file(path)?)?;
+        let config: Config =
+            toml::from_str(&buf).map_err(|e| anyhow!("Invalid {}. {}", path.display(), e))?;
+        if config.files.is_empty() {
+            bail!("Invalid {}. No file is listed", path.display());
+        }
+        Ok(config)
+    }
+
+    /// Opens every listed file, with paths resolved against `base` and the suffixes of
+    /// `known_suffixes` kept as is.
+    pub fn open(
+        &self,
+        base: impl AsRef<Path>,
+        known_suffixes: &[String],
+    ) -> Result<Vec<VersionFile>> {
+        self.files
+            .iter()
+            .map(|file| {
+                Ok(VersionFile::open_with(
+                    base.as_ref().join(&file.path),
+                    file.source()?,
+                    known_suffixes,
+                )?)
+            })
+            .collect()
+    }
+}
+
+impl FileConfig {
+    fn source(&self) -> Result<Box<dyn VersionSource>> {
+        let ext = self.path.extension().and_then(|e| e.to_str());
+        let source: Box<dyn VersionSource> = match (&self.pattern, &self.key, ext) {
+            (Some(_), Some(_), _) => bail!("Specify either pattern or key"),
+            (Some(pattern), None, _) => Box::new(Pattern::new(pattern)?),
+            (None, Some(key), Some("toml")) => Box::new(Toml::new([key.as_str()])),
+            (None, Some(key), Some("json")) => Box::new(Json::new([key.as_str()])),
+            (None, Some(key), Some("yaml" | "yml")) if key.contains('.') => {
+                bail!("key of a YAML file must be a top-level key, not {key}")
+            }
+            (None, Some(key), Some("yaml" | "yml")) => Box::new(Yaml::new(key)),
+            (None, Some(_), _) => bail!("key is only supported for TOML, JSON and YAML files"),
+            (None, None, _) => detect(&self.path),
+        };
+        Ok(source)
+    }
+}
+
+/// Returns the version shared by all `files`, failing if any of them disagree.
+///
+/// Prefixes are not compared, so `v1.0.0` and `1.0.0` agree.
+pub fn current(files: &[VersionFile]) -> Result<Version> {
+    let Some((first, rest)) = files.split_first() else {
+        bail!("No file is listed");
+    };
+    if let Some(file) = rest
+        .iter()
+        .find(|file| file.version().semver() != first.version().semver())
+    {
+        return Err(Error::InvalidFile {
+            path: file.path().to_path_buf(),
+            reason: format!(
+                "Inconsistent versions. {}, while {} has {}",
+                file.version(),
+                first.path().display(),
+                first.version()
+            ),
+        }
+        .into());
+    }
+    Ok(first.version().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::write_file;
+    use tempfile::tempdir;
+
+    const CONFIG: &str = r#"
+[[file]]
+path = "Cargo.toml"
+
+[[file]]
+path = "chart/Chart.yaml"
+key = "appVersion"
+
+[[file]]
+path = "README.md"
+pattern = 'version-(?P<version>[^-]+)-blue'
+"#;
+
+    fn setup(dir: &Path, readme_ver: &str) -> Result<()> {
+        crate::fs::mk_dir(dir.join("chart"))?;
+        write_file(dir.join(CONFIG_FILE), CONFIG.as_bytes())?;
+        write_file(dir.join("Cargo.toml"), b"[package]\nversion = \"1.0.0\"\n")?;
+        write_file(
+            dir.join("chart/Chart.yaml"),
+            b"version: 0.1.0\nappVersion: v1.0.0\n",
+        )?;
+        let readme = format!("![](https://img.shields.io/badge/version-{readme_ver}-blue)\n");
+        write_file(dir.join("README.md"), readme.as_bytes())?;
+        Ok(())
+    }
+
+    #[test]
+    fn open_ok() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        setup(tmp_dir.path(), "1.0.0")?;
+
+        let config = Config::load(tmp_dir.path().join(CONFIG_FILE))?;
+        let files = config.open(tmp_dir.path(), &[])?;
+        assert_eq!(3, files.len());
+        assert_eq!("1.0.0", current(&files)?.to_string());
+        assert_eq!("v1.0.0", files[1].version().to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn current_ng() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        setup(tmp_dir.path(), "0.9.0")?;
+
+        let config = Config::load(tmp_dir.path().join(CONFIG_FILE))?;
+        let files = config.open(tmp_dir.path(), &[])?;
+        let err = current(&files).expect_err("");
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::InvalidFile { .. })
+        ));
+        assert!(err.to_string().contains("Inconsistent versions"));
+        Ok(())
+    }
+
+    #[test]
+    fn source_ng() {
+        let inputs = vec![
+            ("chart/values.yaml", None, Some("image.tag")),
+            ("VERSION", None, Some("version")),
+            (
+                "Cargo.toml",
+                Some("(?P<version>.+)"),
+                Some("package.version"),
+            ),
+        ];
+        for (path, pattern, key) in inputs {
+            let file = FileConfig {
+                path: PathBuf::from(path),
+                pattern: pattern.map(String::from),
+                key: key.map(String::from),
+            };
+            assert!(file.source().is_err(), "{path}");
+        }
+    }
+
+    #[test]
+    fn load_ng() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let path = tmp_dir.path().join(CONFIG_FILE);
+        let inputs = vec![
+            "",
+            "[[file]]\nname = \"VERSION\"\n",
+            "[[file]]\npath = \"VERSION\"\nunknown = 1\n",
+        ];
+        for input in inputs {
+            write_file(&path, input.as_bytes())?;
+            assert!(Config::load(&path).is_err(), "{input}");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+use crate::git::Commit;
+use crate::version::{Level, Version};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static HEADER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?P<type>[[:alnum:]]+)(?:\((?P<scope>[^()\r\n]*)\))?(?P<breaking>!)?: (?P<description>.*)")
+        .unwrap()
+});
+
+static BREAKING_FOOTER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^BREAKING[ -]CHANGE: ").unwrap());
+
+/// A commit message following [Conventional Commits].
+///
+/// [Conventional Commits]: https://www.conventionalcommits.org/
+#[derive(Debug, PartialEq)]
+pub struct Message<'a> {
+    /// Lowercased, like `feat` or `fix`
+    pub kind: String,
+    pub scope: Option<&'a str>,
+    pub breaking: bool,
+    pub description: &'a str,
+}
+
+impl<'a> Message<'a> {
+    pub fn parse(message: &'a str) -> Option<Self> {
+        let caps = HEADER.captures(message)?;
+        Some(Message {
+            kind: caps["type"].to_ascii_lowercase(),
+            scope: caps.name("scope").map(|m| m.as_str()),
+            breaking: caps.name("breaking").is_some() || BREAKING_FOOTER.is_match(message),
+            description: caps
+                .name("description")
+                .map_or("", |m| m.as_str().trim_end()),
+        })
+    }
+
+    pub fn level(&self) -> Option<Level> {
+        match self.kind.as_str() {
+            _ if self.breaking => Some(Level::Major),
+            "feat" => Some(Level::Minor),
+            "fix" => Some(Level::Patch),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the level a commit message asks for, if any.
+pub fn level(message: &str) -> Option<Level> {
+    Message::parse(message)?.level()
+}
+
+/// Returns the highest level asked for by `commits`, along with the commit that asked for it.
+///
+/// Breaking changes only bump minor before 1.0.0, where anything may change at any time.
+pub fn decide<'a>(version: &Version, commits: &'a [Commit]) -> Option<(Level, &'a Commit)> {
+    let (level, commit) = commits
+        .iter()
+        .filter_map(|commit| level(&commit.message).map(|level| (level, commit)))
+        .max_by_key(|(level, _)| *level)?;
+
+    match level {
+        Level::Major if version.semver().major == 0 => Some((Level::Minor, commit)),
+        level => Some((level, commit)),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    /// Returns a log of `messages`, with hashes like `c0` for the first one.
+    pub(crate) fn commits(messages: &[&str]) -> Vec<Commit> {
+        messages
+            .iter()
+            .enumerate()
+            .map(|(i, message)| Commit {
+                hash: format!("c{i}"),
+                message: message.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn level_ok() {
+        let inputs = vec![
+            ("fix: typo", Some(Level::Patch)),
+            ("fix(cli): typo", Some(Level::Patch)),
+            ("feat: add auto", Some(Level::Minor)),
+            ("Feat(args)!: drop --old", Some(Level::Major)),
+            ("refactor!: rename", Some(Level::Major)),
+            (
+                "feat: add\n\nBREAKING CHANGE: drop --old",
+                Some(Level::Major),
+            ),
+            ("fix: x\n\nBREAKING-CHANGE: y", Some(Level::Major)),
+            ("docs: readme", None),
+            ("chore(deps): bump semver", None),
+            ("Merge branch 'main'", None),
+            ("feat:no space", None),
+            ("BREAKING CHANGE: no header", None),
+        ];
+        for (message, expect) in inputs {
+            assert_eq!(expect, level(message), "{message}");
+        }
+    }
+
+    #[test]
+    fn parse_ok() {
+        let expect = Message {
+            kind: String::from("feat"),
+            scope: Some("cli"),
+            breaking: true,
+            description: "drop --old",
+        };
+        assert_eq!(
+            Some(expect),
+            Message::parse("Feat(cli)!: drop --old\n\nbody")
+        );
+
+        let expect = Message {
+            kind: String::from("fix"),
+            scope: None,
+            breaking: false,
+            description: "typo",
+        };
+        assert_eq!(Some(expect), Message::parse("fix: typo"));
+    }
+
+    #[test]
+    fn decide_ok() {
+        let version = Version::from_str("1.2.3").unwrap();
+        let log = commits(&["docs: readme", "feat: b", "fix: c", "feat: a"]);
+        let (level, commit) = decide(&version, &log).unwrap();
+        assert_eq!(Level::Minor, level);
+        assert_eq!("feat: a", commit.message);
+
+        let log = commits(&["feat!: break", "fix: c"]);
+        assert_eq!(Level::Major, decide(&version, &log).unwrap().0);
+
+        let log = commits(&["docs: readme", "ci: lint"]);
+        assert_eq!(None, decide(&version, &log));
+    }
+
+    #[test]
+    fn decide_initial_development_ok() {
+        let version = Version::from_str("0.4.3").unwrap();
+        let log = commits(&["feat!: break", "fix: c"]);
+        assert_eq!(Level::Minor, decide(&version, &log).unwrap().0);
+    }
+}
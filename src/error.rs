@@ -0,0 +1,75 @@
+use crate::version::Part;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Errors raised by this crate that callers may want to tell apart.
+///
+/// Other failures, such as an invalid config or a failing git command, are reported through
+/// [`anyhow::Error`], which wraps these too, so they can be found with
+/// [`anyhow::Error::downcast_ref`].
+#[derive(Debug)]
+pub enum Error {
+    /// A string is not a semantic version
+    Parse { input: String, reason: String },
+    /// A file can't be read or written
+    Io { path: PathBuf, source: io::Error },
+    /// A file without a version where its format has one, or with copies of it that disagree,
+    /// within the file or with the other files of a config
+    InvalidFile { path: PathBuf, reason: String },
+    /// A pre-release that semver does not allow, or that can't be incremented
+    InvalidPreRelease { value: String, reason: String },
+    /// Build metadata that semver does not allow
+    InvalidBuild { value: String, reason: String },
+    /// Arguments that can't be used together, or that are missing
+    ConflictingArguments(String),
+    /// Bumping a part would go past its maximum
+    Overflow { part: Part, version: String },
+    /// A version lower than the current one, when going backwards is not allowed
+    Downgrade { current: String, new: String },
+    /// A template with unknown fields or unmatched braces
+    InvalidTemplate(String),
+}
+
+impl Error {
+    pub(crate) fn io(path: impl Into<PathBuf>) -> impl FnOnce(io::Error) -> Error {
+        move |source| Error::Io {
+            path: path.into(),
+            source,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse { input, reason } => write!(f, "{reason}. value: {input}"),
+            // The io::Error is the source, printed after the path by `{:#}`
+            Error::Io { path, .. } => write!(f, "{}", path.display()),
+            Error::InvalidFile { path, reason } => write!(f, "{}: {reason}", path.display()),
+            Error::InvalidPreRelease { value, reason } => {
+                write!(f, "Invalid pre-release, {reason}. value: {value}")
+            }
+            Error::InvalidBuild { value, reason } => {
+                write!(f, "Invalid build metadata, {reason}. value: {value}")
+            }
+            Error::ConflictingArguments(reason) => write!(f, "Invalid argument, {reason}"),
+            Error::Overflow { part, version } => {
+                write!(f, "Can't bump {part} any further. value: {version}")
+            }
+            Error::Downgrade { current, new } => {
+                write!(f, "Version goes backwards. current: {current}, new: {new}")
+            }
+            Error::InvalidTemplate(reason) => write!(f, "Invalid format, {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
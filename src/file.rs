@@ -0,0 +1,235 @@
+use crate::error::Error;
+use crate::fs::{read_file, write_file_atomic};
+use crate::source::{detect, VersionSource};
+use crate::version::Version;
+use anyhow::Result;
+
+use log::*;
+use similar::TextDiff;
+use std::path::{Path, PathBuf};
+
+/// A version read from a file, along with the source that found it so that it can be rewritten in
+/// place.
+pub struct VersionFile {
+    path: PathBuf,
+    contents: Vec<u8>,
+    source: Box<dyn VersionSource>,
+    version: Version,
+}
+
+impl VersionFile {
+    /// Opens a file with the source picked from its name, see [`detect`].
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        Self::open_with(path, detect(path), &[])
+    }
+
+    /// Opens a file with `source`, keeping the suffixes of `known_suffixes` as is, see
+    /// [`Version::parse_with_suffixes`].
+    pub fn open_with(
+        path: impl AsRef<Path>,
+        source: Box<dyn VersionSource>,
+        known_suffixes: &[String],
+    ) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let contents = read_file(path)?;
+        let version = source
+            .read_with_suffixes(&contents, known_suffixes)
+            .map_err(|err| Error::InvalidFile {
+                path: path.to_path_buf(),
+                reason: format!("{err:#}"),
+            })?;
+        debug!("{}: {:?}", path.display(), version);
+
+        Ok(VersionFile {
+            path: path.to_path_buf(),
+            contents,
+            source,
+            version,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn version(&self) -> &Version {
+        &self.version
+    }
+
+    /// Returns the file contents with the version replaced, leaving everything else untouched.
+    pub fn render(&self, version: &Version) -> Result<Vec<u8>> {
+        self.source.replace(&self.contents, version)
+    }
+
+    /// Returns the change that writing `version` would make.
+    pub fn change(&self, version: &Version) -> Result<Change> {
+        Ok(Change {
+            path: self.path.clone(),
+            before: self.contents.clone(),
+            after: self.render(version)?,
+        })
+    }
+
+    /// Returns a unified diff of the changes that writing `version` would make, empty if none.
+    pub fn diff(&self, version: &Version) -> Result<String> {
+        Ok(self.change(version)?.diff())
+    }
+
+    pub fn write(&self, version: &Version) -> Result<()> {
+        Ok(write_file_atomic(&self.path, &self.render(version)?)?)
+    }
+}
+
+/// New contents for a file, so that every change can be prepared before any of them is written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub path: PathBuf,
+    pub before: Vec<u8>,
+    pub after: Vec<u8>,
+}
+
+impl Change {
+    /// Returns a unified diff of the change, empty if the contents are the same.
+    pub fn diff(&self) -> String {
+        let before = String::from_utf8_lossy(&self.before);
+        let after = String::from_utf8_lossy(&self.after);
+        let path = self.path.display();
+
+        TextDiff::from_lines(&before, &after)
+            .unified_diff()
+            .header(&format!("a/{path}"), &format!("b/{path}"))
+            .to_string()
+    }
+
+    pub fn write(&self) -> Result<()> {
+        debug!("write: {}", self.path.display());
+        Ok(write_file_atomic(&self.path, &self.after)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::write_file;
+    use tempfile::tempdir;
+
+    #[test]
+    fn open_ok() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let path = tmp_dir.path().join("VERSION");
+        write_file(&path, b"v1.2.3\n")?;
+
+        let file = VersionFile::open(&path)?;
+        assert_eq!("v1.2.3", file.version().to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn open_ng() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let path = tmp_dir.path().join("VERSION");
+        write_file(&path, b"version\n")?;
+
+        let actual = VersionFile::open(&path);
+        assert!(matches!(actual, Err(Error::InvalidFile { .. })));
+        let actual = VersionFile::open(tmp_dir.path().join("not_found"));
+        assert!(matches!(actual, Err(Error::Io { .. })));
+        let err = anyhow::Error::from(actual.err().unwrap());
+        assert_eq!(2, err.chain().count());
+        assert!(format!("{err:#}").ends_with(&format!("not_found: {}", err.root_cause())));
+        Ok(())
+    }
+
+    #[test]
+    fn write_keep_trailing_newline_ok() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let path = tmp_dir.path().join("VERSION");
+        write_file(&path, b"1.2.3\n\n")?;
+
+        let file = VersionFile::open(&path)?;
+        file.write(&file.version().bump_minor()?)?;
+        assert_eq!(b"1.3.0\n\n", read_file(&path)?.as_slice());
+        Ok(())
+    }
+
+    #[test]
+    fn diff_ok() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let path = tmp_dir.path().join("Cargo.toml");
+        write_file(&path, b"[package]\nname = \"a\"\nversion = \"0.1.0\"\n")?;
+
+        let file = VersionFile::open(&path)?;
+        let expect = format!(
+            "--- a/{0}\n+++ b/{0}\n@@ -1,3 +1,3 @@\n [package]\n name = \"a\"\n-version = \"0.1.0\"\n+version = \"0.1.1\"\n",
+            path.display()
+        );
+        assert_eq!(expect, file.diff(&file.version().bump_patch()?)?);
+        assert_eq!("", file.diff(file.version())?);
+        Ok(())
+    }
+
+    #[test]
+    fn write_cargo_toml_ok() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let path = tmp_dir.path().join("Cargo.toml");
+        let before = "[package]\nname = \"a\"\nversion = \"0.1.0\" # keep\n\n[dependencies]\nsemver = \"1.0.0\"\n";
+        write_file(&path, before.as_bytes())?;
+
+        let file = VersionFile::open(&path)?;
+        assert_eq!("0.1.0", file.version().to_string());
+        file.write(&file.version().bump_minor()?)?;
+
+        let after = before.replace("0.1.0", "0.2.0");
+        assert_eq!(after.as_bytes(), read_file(&path)?.as_slice());
+        Ok(())
+    }
+
+    #[test]
+    fn write_package_lock_json_ok() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let path = tmp_dir.path().join("package-lock.json");
+        let before = r#"{
+  "name": "app",
+  "version": "1.0.0-rc.1",
+  "lockfileVersion": 3,
+  "packages": {
+    "": { "name": "app", "version": "1.0.0-rc.1" },
+    "node_modules/a": { "version": "1.0.0-rc.1" }
+  }
+}
+"#;
+        write_file(&path, before.as_bytes())?;
+
+        let file = VersionFile::open(&path)?;
+        file.write(&file.version().bump_patch()?)?;
+
+        let after = before.replacen("1.0.0-rc.1", "1.0.1", 2);
+        assert_eq!(after, String::from_utf8(read_file(&path)?)?);
+        Ok(())
+    }
+
+    #[test]
+    fn write_keep_suffix_ok() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let path = tmp_dir.path().join("ARTIFACT");
+        write_file(&path, b"app-1.2.3.tar.gz\n")?;
+
+        let file = VersionFile::open(&path)?;
+        file.write(&file.version().bump_minor()?)?;
+        assert_eq!(b"app-1.3.0.tar.gz\n", read_file(&path)?.as_slice());
+        Ok(())
+    }
+
+    #[test]
+    fn open_inconsistent_ng() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let path = tmp_dir.path().join("package-lock.json");
+        let contents = r#"{"version": "1.0.0", "packages": {"": {"version": "1.0.1"}}}"#;
+        write_file(&path, contents.as_bytes())?;
+
+        let actual = VersionFile::open(&path);
+        assert!(actual.is_err());
+        Ok(())
+    }
+}
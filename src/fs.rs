@@ -2,8 +2,10 @@ use std::fs::{self, File};
 use std::io::{BufReader, BufWriter, Read, Write};
 use std::path::Path;
 
+use crate::error::Error;
 use anyhow::Result;
 use std::io;
+use tempfile::NamedTempFile;
 
 #[allow(dead_code)]
 pub fn mk_dir(path: impl AsRef<Path>) -> Result<()> {
@@ -11,28 +13,55 @@ pub fn mk_dir(path: impl AsRef<Path>) -> Result<()> {
     Ok(())
 }
 
-#[allow(dead_code)]
-pub fn read_file(path: impl AsRef<Path>) -> Result<Vec<u8>> {
-    let file = File::open(&path)?;
+pub fn read_file(path: impl AsRef<Path>) -> Result<Vec<u8>, Error> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(Error::io(path))?;
     let mut reader = BufReader::new(file);
     let mut buf = Vec::new();
-    reader.read_to_end(&mut buf)?;
+    reader.read_to_end(&mut buf).map_err(Error::io(path))?;
     Ok(buf)
 }
 
-#[allow(dead_code)]
-pub fn write_file(path: impl AsRef<Path>, contents: &[u8]) -> Result<()> {
-    let file = File::create(path)?;
+pub fn write_file(path: impl AsRef<Path>, contents: &[u8]) -> Result<(), Error> {
+    let path = path.as_ref();
+    let file = File::create(path).map_err(Error::io(path))?;
     let mut buf = BufWriter::new(file);
-    buf.write_all(contents)?;
+    buf.write_all(contents).map_err(Error::io(path))?;
+    Ok(())
+}
+
+/// Replaces `path` with `contents` without ever leaving a partially written file behind.
+///
+/// The contents are written to a temporary file in the same directory, which then takes over the
+/// permissions of the original file and is renamed onto it. A symlink is followed, so that its
+/// target is replaced rather than the link itself.
+pub fn write_file_atomic(path: impl AsRef<Path>, contents: &[u8]) -> Result<(), Error> {
+    let path = path.as_ref();
+    // A file that doesn't exist yet has nothing to resolve
+    let path = &fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let permissions = fs::metadata(path).map(|m| m.permissions()).ok();
+
+    let mut tmp = NamedTempFile::new_in(dir).map_err(Error::io(dir))?;
+    tmp.write_all(contents).map_err(Error::io(tmp.path()))?;
+    tmp.as_file().sync_all().map_err(Error::io(tmp.path()))?;
+    if let Some(permissions) = permissions {
+        fs::set_permissions(tmp.path(), permissions).map_err(Error::io(tmp.path()))?;
+    }
+    tmp.persist(path).map_err(|e| Error::io(path)(e.error))?;
     Ok(())
 }
 
-pub fn read_from_stdin() -> Result<String> {
+pub fn read_from_stdin() -> Result<String, Error> {
     let mut buf = String::new();
     let stdin = io::stdin();
     let mut handle = stdin.lock();
-    handle.read_to_string(&mut buf)?;
+    handle
+        .read_to_string(&mut buf)
+        .map_err(Error::io("stdin"))?;
     Ok(buf)
 }
 
@@ -97,4 +126,49 @@ mod tests {
         assert!(actual.is_ok());
         Ok(())
     }
+
+    #[test]
+    fn write_file_atomic_ok() -> Result<()> {
+        let file = "atomic_test.txt";
+        let tmp_dir = tempdir()?;
+        let tmp_file = tmp_dir.path().join(file);
+        write_file(&tmp_file, b"before")?;
+
+        let actual = write_file_atomic(&tmp_file, b"after");
+        assert!(actual.is_ok());
+        assert_eq!(b"after", read_file(&tmp_file)?.as_slice());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_file_atomic_keep_permissions_ok() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let file = "atomic_perm_test.txt";
+        let tmp_dir = tempdir()?;
+        let tmp_file = tmp_dir.path().join(file);
+        write_file(&tmp_file, b"before")?;
+        fs::set_permissions(&tmp_file, fs::Permissions::from_mode(0o644))?;
+
+        write_file_atomic(&tmp_file, b"after")?;
+        let mode = fs::metadata(&tmp_file)?.permissions().mode();
+        assert_eq!(0o644, mode & 0o777);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_file_atomic_symlink_ok() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let target = tmp_dir.path().join("target.txt");
+        let link = tmp_dir.path().join("link.txt");
+        write_file(&target, b"before")?;
+        std::os::unix::fs::symlink(&target, &link)?;
+
+        write_file_atomic(&link, b"after")?;
+        assert!(fs::symlink_metadata(&link)?.file_type().is_symlink());
+        assert_eq!(b"after", read_file(&target)?.as_slice());
+        Ok(())
+    }
 }
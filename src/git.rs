@@ -0,0 +1,209 @@
+use crate::version::Version;
+use anyhow::{anyhow, bail, Result};
+
+use log::*;
+use std::path::Path;
+use std::process::Command;
+
+/// Runs git in `repo` and returns its stdout.
+fn git(repo: &Path, args: &[&str]) -> Result<String> {
+    debug!("git {}", args.join(" "));
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .map_err(|e| anyhow!("Failed to run git. {e}"))?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed. {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        );
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Whether the working tree has uncommitted changes, untracked files included.
+pub fn is_dirty(repo: impl AsRef<Path>) -> Result<bool> {
+    let out = git(repo.as_ref(), &["status", "--porcelain"])?;
+    Ok(!out.is_empty())
+}
+
+pub fn tag_exists(repo: impl AsRef<Path>, name: &str) -> Result<bool> {
+    Ok(tags(repo)?.iter().any(|tag| tag == name))
+}
+
+/// Fails unless `name` can be tagged, that is the tag is new and the working tree is clean.
+pub fn check_tag(repo: impl AsRef<Path>, name: &str) -> Result<()> {
+    let repo = repo.as_ref();
+    if tag_exists(repo, name)? {
+        bail!("Tag already exists. tag: {name}");
+    }
+    if is_dirty(repo)? {
+        bail!("Working tree is dirty, commit or stash the changes first");
+    }
+    Ok(())
+}
+
+/// Creates an annotated tag on HEAD.
+pub fn create_tag(repo: impl AsRef<Path>, name: &str, message: &str) -> Result<()> {
+    git(
+        repo.as_ref(),
+        &["tag", "--annotate", name, "--message", message],
+    )?;
+    Ok(())
+}
+
+pub fn tags(repo: impl AsRef<Path>) -> Result<Vec<String>> {
+    let out = git(repo.as_ref(), &["tag", "--list"])?;
+    Ok(out.lines().map(String::from).collect())
+}
+
+/// Returns the tag with the highest version by semver precedence, ignoring tags that are not
+/// versions. The suffixes of `known_suffixes` are kept as is, see [`Version::parse_with_suffixes`].
+pub fn latest_tag(
+    repo: impl AsRef<Path>,
+    known_suffixes: &[String],
+) -> Result<Option<(String, Version)>> {
+    let tag = tags(repo)?
+        .into_iter()
+        .filter_map(
+            |tag| match Version::parse_with_suffixes(&tag, known_suffixes) {
+                Ok(v) => Some((tag, v)),
+                Err(err) => {
+                    debug!("skip tag {tag}: {err}");
+                    None
+                }
+            },
+        )
+        .max_by(|(_, a), (_, b)| a.cmp_precedence(b));
+    Ok(tag)
+}
+
+pub fn latest_version(repo: impl AsRef<Path>, known_suffixes: &[String]) -> Result<Version> {
+    latest_tag(repo, known_suffixes)?
+        .map(|(_, version)| version)
+        .ok_or_else(|| anyhow!("Can't find a version tag"))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Commit {
+    pub hash: String,
+    pub message: String,
+}
+
+impl Commit {
+    pub fn subject(&self) -> &str {
+        self.message.lines().next().unwrap_or_default()
+    }
+}
+
+/// Returns the commits reachable from HEAD but not from `since`, newest first, or the whole
+/// history without `since`.
+pub fn commits_since(repo: impl AsRef<Path>, since: Option<&str>) -> Result<Vec<Commit>> {
+    let range = since.map(|tag| format!("{tag}..HEAD"));
+    let mut args = vec!["log", "--format=%h%x1f%B%x1e"];
+    args.push(range.as_deref().unwrap_or("HEAD"));
+
+    let out = git(repo.as_ref(), &args)?;
+    let commits = out
+        .split('\x1e')
+        .filter_map(|record| {
+            let (hash, message) = record.trim_start_matches('\n').split_once('\x1f')?;
+            Some(Commit {
+                hash: hash.to_string(),
+                message: message.trim_end().to_string(),
+            })
+        })
+        .collect();
+    Ok(commits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::{tempdir, TempDir};
+
+    /// Creates a repository with one empty commit tagged with each of `tags`.
+    fn repo(tags: &[&str]) -> Result<TempDir> {
+        let dir = tempdir()?;
+        git(dir.path(), &["init", "-q"])?;
+        git(dir.path(), &["config", "user.name", "bump"])?;
+        git(dir.path(), &["config", "user.email", "bump@example.com"])?;
+        commit(dir.path(), "initial commit")?;
+        for tag in tags {
+            git(dir.path(), &["tag", tag])?;
+        }
+        Ok(dir)
+    }
+
+    fn commit(repo: &Path, message: &str) -> Result<()> {
+        git(repo, &["commit", "-q", "--allow-empty", "-m", message])?;
+        Ok(())
+    }
+
+    #[test]
+    fn latest_version_ok() -> Result<()> {
+        let dir = repo(&["v1.2.0", "v1.10.0", "v1.10.0-rc.1", "latest", "v1.9.3"])?;
+        assert_eq!("v1.10.0", latest_version(dir.path(), &[])?.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn create_tag_ok() -> Result<()> {
+        let dir = repo(&["v1.0.0"])?;
+        check_tag(dir.path(), "v1.0.1")?;
+        create_tag(dir.path(), "v1.0.1", "Release v1.0.1")?;
+
+        assert!(tag_exists(dir.path(), "v1.0.1")?);
+        let kind = git(dir.path(), &["cat-file", "-t", "v1.0.1"])?;
+        assert_eq!("tag", kind.trim_end());
+        let message = git(
+            dir.path(),
+            &["tag", "--list", "--format=%(contents)", "v1.0.1"],
+        )?;
+        assert_eq!("Release v1.0.1", message.trim_end());
+        Ok(())
+    }
+
+    #[test]
+    fn check_tag_ng() -> Result<()> {
+        let dir = repo(&["v1.0.0"])?;
+        assert!(check_tag(dir.path(), "v1.0.0").is_err());
+
+        std::fs::write(dir.path().join("VERSION"), "v1.0.1\n")?;
+        let msg = check_tag(dir.path(), "v1.0.1").expect_err("").to_string();
+        assert!(msg.contains("dirty"));
+        Ok(())
+    }
+
+    #[test]
+    fn commits_since_ok() -> Result<()> {
+        let dir = repo(&["v1.0.0"])?;
+        commit(dir.path(), "fix: one\n\nbody")?;
+        commit(dir.path(), "feat: two")?;
+
+        let commits = commits_since(dir.path(), Some("v1.0.0"))?;
+        let messages = commits
+            .iter()
+            .map(|c| c.message.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["feat: two", "fix: one\n\nbody"], messages);
+        assert_eq!("fix: one", commits[1].subject());
+
+        let commits = commits_since(dir.path(), None)?;
+        assert_eq!(3, commits.len());
+        Ok(())
+    }
+
+    #[test]
+    fn latest_version_ng() -> Result<()> {
+        let dir = repo(&["latest"])?;
+        assert!(latest_version(dir.path(), &[]).is_err());
+
+        let dir = tempdir()?;
+        assert!(latest_version(dir.path(), &[]).is_err());
+        Ok(())
+    }
+}
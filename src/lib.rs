@@ -1,2 +1,12 @@
+pub mod changelog;
+pub mod config;
+pub mod conventional;
+pub mod error;
+pub mod file;
 pub mod fs;
+pub mod git;
+pub mod source;
+pub mod validate;
 pub mod version;
+
+pub use crate::error::Error;
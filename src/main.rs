@@ -1,49 +1,518 @@
 mod args;
-use crate::args::{Args, SubCommand};
-use bump_bin::version::Version;
+use crate::args::{Args, Input, OutputFormat, SubCommand};
+use bump_bin::config::{current, Config};
+use bump_bin::file::{Change, VersionFile};
+use bump_bin::fs::{read_file, read_from_stdin};
+use bump_bin::git;
+use bump_bin::source::{detect, Pattern, VersionSource};
+use bump_bin::version::{Level, Version};
+use bump_bin::{changelog, conventional, validate, Error};
 use clap::Parser;
 use log::*;
+use semver::VersionReq;
+use serde_json::json;
 
-use anyhow::{bail, Result};
+use anyhow::Result;
+use std::cmp::Ordering;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use std::process::exit;
+use std::str::FromStr;
 
 const EXIT_CODE_OK: i32 = 0;
+const EXIT_CODE_CHANGED: i32 = 1;
 const EXIT_CODE_USAGE: i32 = 2;
+const EXIT_CODE_MISMATCH: i32 = 1;
+const EXIT_CODE_PARSE: i32 = 3;
+const EXIT_CODE_INVALID_PRE: i32 = 4;
+const EXIT_CODE_INVALID_BUILD: i32 = 5;
+const EXIT_CODE_OVERFLOW: i32 = 6;
+const EXIT_CODE_IO: i32 = 7;
+const EXIT_CODE_DOWNGRADE: i32 = 8;
 
-fn run(args: Args) -> Result<String> {
-    let subcommand = move || -> Result<Version> {
-        let version = match args.sub {
-            SubCommand::Patch { file, ver } => {
-                let ver = Version::try_from((file, ver))?;
-                ver.bump_patch()
-            }
-            SubCommand::Minor { file, ver } => {
-                let ver = Version::try_from((file, ver))?;
-                ver.bump_minor()
-            }
-            SubCommand::Major { file, ver } => {
-                let ver = Version::try_from((file, ver))?;
-                ver.bump_major()
-            }
-            SubCommand::Pre { file, pre, ver } => {
-                let ver = Version::try_from((file, ver))?;
-                ver.update_pre_release(pre)?
-            }
-            SubCommand::Build { file, build, ver } => {
-                let ver = Version::try_from((file, ver))?;
-                ver.update_build(build)?
-            }
+/// What to print, if anything, and the exit code to leave with
+#[derive(Debug, PartialEq)]
+struct Output {
+    view: Option<String>,
+    code: i32,
+}
+
+/// Returns the exit code for an error, by its category when it is one of [`Error`].
+fn exit_code(err: &anyhow::Error) -> i32 {
+    match err.downcast_ref::<Error>() {
+        Some(Error::Parse { .. } | Error::InvalidFile { .. }) => EXIT_CODE_PARSE,
+        Some(Error::Io { .. }) => EXIT_CODE_IO,
+        Some(Error::InvalidPreRelease { .. }) => EXIT_CODE_INVALID_PRE,
+        Some(Error::InvalidBuild { .. }) => EXIT_CODE_INVALID_BUILD,
+        Some(Error::Overflow { .. }) => EXIT_CODE_OVERFLOW,
+        Some(Error::Downgrade { .. }) => EXIT_CODE_DOWNGRADE,
+        Some(Error::ConflictingArguments(_) | Error::InvalidTemplate(_)) | None => EXIT_CODE_USAGE,
+    }
+}
+
+fn conflict(reason: &str) -> anyhow::Error {
+    Error::ConflictingArguments(reason.to_string()).into()
+}
+
+/// Returns the current version and the files to rewrite with the bumped one.
+///
+/// With `auto`, which reads no version from stdin, the latest version tag is the fallback.
+fn load(input: &Input, ver: Option<&String>, auto: bool) -> Result<(Version, Vec<VersionFile>)> {
+    if input.from_git {
+        return Ok((git::latest_version(".", &input.known_suffixes)?, vec![]));
+    }
+
+    let config = match (&input.config, &input.file, ver) {
+        (Some(config), _, _) => Some(PathBuf::from(config)),
+        // A version piped to stdin wins over the config of the current directory
+        (None, None, None) if auto || io::stdin().is_terminal() => Config::discover(),
+        _ => None,
+    };
+    if let Some(config) = config {
+        debug!("config: {}", config.display());
+        let base = config.parent().unwrap_or(Path::new("")).to_path_buf();
+        let files = Config::load(&config)?.open(base, &input.known_suffixes)?;
+        let version = current(&files)?;
+        // As with --file, the files of the config are only rewritten with --write
+        let files = if input.write || input.dry_run {
+            files
+        } else {
+            vec![]
         };
+        return Ok((version, files));
+    }
+
+    match (&input.file, ver) {
+        (Some(f), None) if f != "-" => {
+            let source: Box<dyn VersionSource> = match &input.pattern {
+                Some(pattern) => Box::new(Pattern::new(pattern)?),
+                None => detect(f),
+            };
+            let file = VersionFile::open_with(f, source, &input.known_suffixes)?;
+            let version = file.version().clone();
+            let files = if input.write || input.dry_run {
+                vec![file]
+            } else {
+                vec![]
+            };
+            Ok((version, files))
+        }
+        _ if input.write => Err(conflict(
+            "--write requires a file other than stdin, or a config",
+        )),
+        _ if input.pattern.is_some() => Err(conflict("--pattern requires a file other than stdin")),
+        (None, None) if auto => Ok((git::latest_version(".", &input.known_suffixes)?, vec![])),
+        (None, Some(ver)) => Ok((
+            Version::parse_with_suffixes(ver, &input.known_suffixes)?,
+            vec![],
+        )),
+        // Only `-` is left as a file here
+        (file, None) if file.is_some() || !io::stdin().is_terminal() => {
+            let buf = read_from_stdin()?;
+            let version = Version::parse_with_suffixes(buf.trim_end(), &input.known_suffixes)?;
+            Ok((version, vec![]))
+        }
+        (file, ver) => Ok((Version::try_from((file.clone(), ver.cloned()))?, vec![])),
+    }
+}
+
+/// Parses versions given one per line, skipping blank lines and the ones that aren't a version,
+/// like a `latest` tag. Skipped versions are reported to `errors`.
+fn parse_lines(lines: &str, mut errors: impl Write) -> Result<Vec<(Version, &str)>> {
+    let mut versions = vec![];
+    for (i, line) in lines.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match Version::from_str(line) {
+            Ok(version) => versions.push((version, line)),
+            Err(err) => writeln!(errors, "skipped line {}: {err}", i + 1)?,
+        }
+    }
+    Ok(versions)
+}
+
+/// Sorts versions given one per line, by prefix and then by precedence.
+fn sort(lines: &str, reverse: bool, errors: impl Write) -> Result<String> {
+    let mut versions = parse_lines(lines, errors)?;
+    versions.sort_by(|(a, _), (b, _)| a.prefix().cmp(b.prefix()).then_with(|| a.cmp_precedence(b)));
+    if reverse {
+        versions.reverse();
+    }
+    Ok(versions
+        .into_iter()
+        .map(|(_, line)| line)
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Returns the highest of the versions given one per line that satisfies `req`.
+fn max_satisfying<'a>(
+    req: &VersionReq,
+    lines: &'a str,
+    errors: impl Write,
+) -> Result<Option<&'a str>> {
+    Ok(parse_lines(lines, errors)?
+        .into_iter()
+        .filter(|(version, _)| req.matches(version.semver()))
+        .max_by(|(a, _), (b, _)| a.cmp_precedence(b))
+        .map(|(_, line)| line))
+}
+
+/// Renders the bumped version, along with the previous one for json.
+fn report(previous: &Version, version: &Version, output: OutputFormat) -> String {
+    match output {
+        OutputFormat::Text => version.to_string(),
+        OutputFormat::Json => {
+            let ver = version.semver();
+            let optional = |s: &str| (!s.is_empty()).then(|| s.to_string());
+            json!({
+                "prefix": version.prefix(),
+                "major": ver.major,
+                "minor": ver.minor,
+                "patch": ver.patch,
+                "pre": optional(ver.pre.as_str()),
+                "build": optional(ver.build.as_str()),
+                "suffix": optional(version.suffix()),
+                "previous": previous.to_string(),
+                "bumped_part": version.changed_part(previous),
+            })
+            .to_string()
+        }
+    }
+}
 
-        Ok(version)
+/// Renders the bumped version as --format or --output asks.
+fn render(input: &Input, previous: &Version, version: &Version) -> Result<String> {
+    match &input.format {
+        Some(template) => Ok(version.format(template)?),
+        None => Ok(report(previous, version, input.output)),
+    }
+}
+
+/// Bumps every version read from `reader`, one per line, writing each result as soon as it is
+/// known. Blank lines are skipped.
+///
+/// Stops at the first line that fails unless --keep-going is given, in which case failures are
+/// reported to `errors` and the exit code of the first one is returned, as it would be without
+/// --keep-going.
+fn bump_lines(
+    sub: &SubCommand,
+    input: &Input,
+    reader: impl BufRead,
+    mut writer: impl Write,
+    mut errors: impl Write,
+) -> Result<i32> {
+    let mut failed = 0;
+    let mut code = EXIT_CODE_OK;
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let bumped = Version::parse_with_suffixes(line, &input.known_suffixes)
+            .map_err(anyhow::Error::from)
+            .and_then(|current| {
+                let version = bump(sub, input, &current, &mut errors)?;
+                render(input, &current, &version)
+            });
+        match bumped {
+            Ok(view) => writeln!(writer, "{view}")?,
+            Err(err) if input.keep_going => {
+                writeln!(errors, "line {}: {err:#}", i + 1)?;
+                if failed == 0 {
+                    code = exit_code(&err);
+                }
+                failed += 1;
+            }
+            Err(err) => return Err(err.context(format!("line {}", i + 1))),
+        }
+    }
+    if failed > 0 {
+        writeln!(errors, "{failed} line(s) failed")?;
+    }
+    Ok(code)
+}
+
+/// Reports whether `ver` is a semantic version, pointing at the first problem if not.
+fn validate(ver: &str, output: OutputFormat) -> Output {
+    let result = validate::validate(ver);
+    let code = match result {
+        Ok(()) => EXIT_CODE_OK,
+        Err(_) => EXIT_CODE_MISMATCH,
+    };
+    let view = match (output, result) {
+        (OutputFormat::Text, Ok(())) => ver.to_string(),
+        (OutputFormat::Text, Err(diag)) => {
+            let column = ver[..diag.offset].chars().count();
+            format!("{diag}\n{ver}\n{}^", " ".repeat(column))
+        }
+        (OutputFormat::Json, Ok(())) => json!({ "input": ver, "valid": true }).to_string(),
+        (OutputFormat::Json, Err(diag)) => json!({
+            "input": ver,
+            "valid": false,
+            "kind": diag.kind,
+            "offset": diag.offset,
+            "message": diag.message,
+        })
+        .to_string(),
+    };
+    Output {
+        view: Some(view),
+        code,
+    }
+}
+
+/// Runs the subcommands that only look at versions, without bumping one.
+fn query(sub: SubCommand) -> Result<Output> {
+    match sub {
+        SubCommand::Compare { expect, a, b } => {
+            let ordering = Version::from_str(&a)?.cmp_precedence(&Version::from_str(&b)?);
+            let view = match ordering {
+                Ordering::Less => "<",
+                Ordering::Equal => "=",
+                Ordering::Greater => ">",
+            };
+            let code = match expect {
+                Some(relation) if !relation.holds(ordering) => EXIT_CODE_MISMATCH,
+                _ => EXIT_CODE_OK,
+            };
+            Ok(Output {
+                view: Some(view.to_string()),
+                code,
+            })
+        }
+        SubCommand::Sort { reverse } => {
+            let sorted = sort(&read_from_stdin()?, reverse, io::stderr())?;
+            Ok(Output {
+                // Nothing to print when no line is a version
+                view: (!sorted.is_empty()).then_some(sorted),
+                code: EXIT_CODE_OK,
+            })
+        }
+        SubCommand::Satisfies {
+            max_satisfying: true,
+            req,
+            ..
+        } => {
+            let req = VersionReq::parse(&req)?;
+            let lines = read_from_stdin()?;
+            Ok(match self::max_satisfying(&req, &lines, io::stderr())? {
+                Some(line) => Output {
+                    view: Some(line.to_string()),
+                    code: EXIT_CODE_OK,
+                },
+                None => Output {
+                    view: None,
+                    code: EXIT_CODE_MISMATCH,
+                },
+            })
+        }
+        SubCommand::Satisfies { req, ver, .. } => {
+            let req = VersionReq::parse(&req)?;
+            let version = Version::try_from((None, ver))?;
+            let matches = req.matches(version.semver());
+            Ok(Output {
+                view: Some(matches.to_string()),
+                code: if matches {
+                    EXIT_CODE_OK
+                } else {
+                    EXIT_CODE_MISMATCH
+                },
+            })
+        }
+        SubCommand::Validate { output, ver } => Ok(validate(&ver, output)),
+        SubCommand::Get { file, part, ver } => Ok(Output {
+            view: Some(Version::try_from((file, ver))?.get(part.into())),
+            code: EXIT_CODE_OK,
+        }),
+        _ => unreachable!("the subcommand bumps a version"),
+    }
+}
+
+/// Returns `current` bumped as the subcommand asks.
+fn next_version(sub: &SubCommand, current: &Version) -> Result<Version> {
+    let version = match sub {
+        SubCommand::Patch { pre_policy, .. } => {
+            current.bump_with(Level::Patch, (*pre_policy).into())?
+        }
+        SubCommand::Minor { pre_policy, .. } => {
+            current.bump_with(Level::Minor, (*pre_policy).into())?
+        }
+        SubCommand::Major { pre_policy, .. } => {
+            current.bump_with(Level::Major, (*pre_policy).into())?
+        }
+        SubCommand::Auto {
+            input,
+            explain,
+            pre_policy,
+            ..
+        } => {
+            let since = git::latest_tag(".", &input.known_suffixes)?.map(|(tag, _)| tag);
+            let commits = git::commits_since(".", since.as_deref())?;
+            match conventional::decide(current, &commits) {
+                Some((level, commit)) => {
+                    if *explain {
+                        eprintln!("{level}: {} {}", commit.hash, commit.subject());
+                    }
+                    current.bump_with(level, (*pre_policy).into())?
+                }
+                None => {
+                    if *explain {
+                        eprintln!("none: no commit asks for a release");
+                    }
+                    current.clone()
+                }
+            }
+        }
+        SubCommand::Prepatch { preid, .. } => current.bump_pre(Level::Patch, preid.as_str())?,
+        SubCommand::Preminor { preid, .. } => current.bump_pre(Level::Minor, preid.as_str())?,
+        SubCommand::Premajor { preid, .. } => current.bump_pre(Level::Major, preid.as_str())?,
+        SubCommand::Prerelease { preid, .. } => current.bump_pre_release(preid.as_deref())?,
+        SubCommand::Release { strip_build, .. } if *strip_build => {
+            current.release().update_build("")?
+        }
+        SubCommand::Release { .. } => current.release(),
+        SubCommand::Set {
+            new,
+            allow_downgrade,
+            ..
+        } => current.set(&Version::from_str(new)?, *allow_downgrade)?,
+        SubCommand::Pre { pre, next, .. } if *next => current.next_pre_release(pre.as_str())?,
+        SubCommand::Pre { pre, .. } => current.update_pre_release(pre.as_str())?,
+        SubCommand::Build { build, .. } => current.update_build(build.as_str())?,
+        SubCommand::Compare { .. }
+        | SubCommand::Sort { .. }
+        | SubCommand::Satisfies { .. }
+        | SubCommand::Validate { .. }
+        | SubCommand::Get { .. } => unreachable!("the subcommand has no input"),
     };
+    Ok(version)
+}
+
+/// Bumps `current` as the subcommand asks, checking and rewriting the prefix as the options ask.
+///
+/// Warnings go to `warnings`, which is stderr unless --lines gathers them with its errors.
+fn bump(
+    sub: &SubCommand,
+    input: &Input,
+    current: &Version,
+    mut warnings: impl Write,
+) -> Result<Version> {
+    let prefix = input.prefix.as_deref().unwrap_or_default();
+    if prefix.ends_with(|c: char| c.is_ascii_digit()) {
+        return Err(conflict("--prefix must not end with a digit"));
+    }
+    current.check_prefix(&input.allowed_prefixes)?;
+    let version = next_version(sub, current)?;
+    if current.pre_looks_like_suffix() && version.semver().pre.is_empty() {
+        writeln!(
+            warnings,
+            "warning: dropped the pre-release '{}' of {current}, declare it with --known-suffixes if it is a suffix",
+            current.semver().pre
+        )?;
+    }
+    Ok(version.with_prefix(input.prefix(version.prefix())))
+}
 
-    let version = match subcommand() {
-        Ok(v) => v,
-        Err(err) => bail!("{err}"),
+fn run(args: Args) -> Result<Output> {
+    let Some((input, ver)) = args.sub.input() else {
+        return query(args.sub);
     };
+    if input.lines {
+        let stdin = io::stdin().lock();
+        let code = bump_lines(&args.sub, input, stdin, io::stdout(), io::stderr())?;
+        // Each line has been printed already
+        return Ok(Output { view: None, code });
+    }
+    let auto = matches!(args.sub, SubCommand::Auto { .. });
+    let (current, files) = load(input, ver, auto)?;
+    let dry_run = input.dry_run;
+    let tag = input.tag.then(|| input.tag_message.clone());
+    let changelog_file = input.changelog.clone();
+
+    let version = bump(&args.sub, input, &current, io::stderr())?;
+
     debug!("version: {:?}", &version);
-    Ok(version.to_string())
+
+    // Check before writing anything, files rewritten below would make the tree dirty
+    let name = version.to_string();
+    let view = render(input, &current, &version)?;
+    if tag.is_some() {
+        git::check_tag(".", &name)?;
+    }
+
+    // Render everything first, so that nothing is written if any file fails
+    let mut changes = files
+        .iter()
+        .map(|file| {
+            let before = file.version();
+            // Files of a config may differ in prefix and suffix, each keeps its own
+            let after = version
+                .with_prefix(before.prefix())
+                .with_suffix(before.suffix());
+            file.change(&after)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if let Some(path) = changelog_file.filter(|_| version != current) {
+        let since = git::latest_tag(".", &input.known_suffixes)?.map(|(tag, _)| tag);
+        let commits = git::commits_since(".", since.as_deref())?;
+        let section = changelog::section(&version, &changelog::today(), &commits);
+        let before = if Path::new(&path).exists() {
+            read_file(&path)?
+        } else {
+            vec![]
+        };
+        let after = changelog::insert(&String::from_utf8(before.clone())?, &section);
+        changes.push(Change {
+            path: PathBuf::from(path),
+            before,
+            after: after.into_bytes(),
+        });
+    }
+    // The tag would point at a commit that still has the current version
+    if tag.is_some() && changes.iter().any(|change| change.before != change.after) {
+        return Err(conflict(
+            "--tag can't be used while rewriting files, commit them and tag afterwards",
+        ));
+    }
+
+    if dry_run {
+        let diff = changes.iter().map(Change::diff).collect::<String>();
+        let code = if diff.is_empty() {
+            EXIT_CODE_OK
+        } else {
+            EXIT_CODE_CHANGED
+        };
+        // Keep stdout a single json document
+        let view = match input.output {
+            OutputFormat::Text => format!("{diff}{view}"),
+            OutputFormat::Json => {
+                eprint!("{diff}");
+                view
+            }
+        };
+        return Ok(Output {
+            view: Some(view),
+            code,
+        });
+    }
+
+    for change in &changes {
+        change.write()?;
+    }
+
+    if let Some(message) = tag {
+        debug!("tag: {name}");
+        git::create_tag(".", &name, message.as_deref().unwrap_or(&name))?;
+    }
+
+    Ok(Output {
+        view: Some(view),
+        code: EXIT_CODE_OK,
+    })
 }
 
 fn main() {
@@ -52,13 +521,15 @@ fn main() {
     let args = Args::parse();
 
     let code = match run(args) {
-        Ok(view) => {
-            println!("{view}");
-            EXIT_CODE_OK
+        Ok(Output { view, code }) => {
+            if let Some(view) = view {
+                println!("{view}");
+            }
+            code
         }
         Err(err) => {
-            eprintln!("{err}");
-            EXIT_CODE_USAGE
+            eprintln!("{err:#}");
+            exit_code(&err)
         }
     };
     exit(code)
@@ -74,7 +545,7 @@ mod tests {
         let args = Args::try_parse_from(row_args)?;
 
         let actual = run(args);
-        assert_eq!(actual.unwrap(), String::from(expect));
+        assert_eq!(actual.unwrap().view, Some(String::from(expect)));
         Ok(())
     }
 
@@ -177,6 +648,459 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn next_pre_ok() -> Result<()> {
+        let version = "1.0.0-rc.3";
+        let expect = "1.0.0-rc.4";
+        let args = vec!["bump", "pre", "--next", "rc", version];
+        test_ok(args, expect)?;
+
+        let version = "1.0.0-alpha.3";
+        let expect = "1.0.0-beta.0";
+        let args = vec!["bump", "pre", "--next", "beta", version];
+        test_ok(args, expect)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn pre_bump_ok() -> Result<()> {
+        let version = "1.2.3";
+        test_ok(vec!["bump", "prepatch", version], "1.2.4-rc.0")?;
+        test_ok(vec!["bump", "preminor", version], "1.3.0-rc.0")?;
+        test_ok(
+            vec!["bump", "premajor", "--preid", "beta", version],
+            "2.0.0-beta.0",
+        )?;
+        test_ok(vec!["bump", "prerelease", version], "1.2.4-rc.0")?;
+        test_ok(
+            vec!["bump", "prerelease", "v1.3.0-alpha.1"],
+            "v1.3.0-alpha.2",
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn release_ok() -> Result<()> {
+        test_ok(vec!["bump", "release", "2.0.0-rc.4"], "2.0.0")?;
+        test_ok(vec!["bump", "release", "2.0.0-rc.4+b1"], "2.0.0+b1")?;
+        test_ok(
+            vec!["bump", "release", "--strip-build", "2.0.0-rc.4+b1"],
+            "2.0.0",
+        )?;
+        test_ok(vec!["bump", "release", "v2.0.0"], "v2.0.0")?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn pre_policy_ok() -> Result<()> {
+        let version = "1.1.0-rc.1";
+        test_ok(vec!["bump", "minor", version], "1.2.0")?;
+        test_ok(
+            vec!["bump", "minor", "--pre-policy", "increment", version],
+            "1.2.0",
+        )?;
+        test_ok(
+            vec!["bump", "minor", "--pre-policy", "finalize", version],
+            "1.1.0",
+        )?;
+        test_ok(
+            vec!["bump", "patch", "--pre-policy", "finalize", version],
+            "1.1.0",
+        )?;
+        test_ok(
+            vec!["bump", "major", "--pre-policy", "finalize", version],
+            "2.0.0",
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_ok() -> Result<()> {
+        test_ok(vec!["bump", "set", "3.0.0", "v2.1.0"], "v3.0.0")?;
+        test_ok(vec!["bump", "set", "2.1.0", "2.1.0"], "2.1.0")?;
+        test_ok(
+            vec!["bump", "set", "--allow-downgrade", "1.0.0", "2.1.0"],
+            "1.0.0",
+        )?;
+
+        let tmp_dir = tempdir()?;
+        let version_file = tmp_dir.path().join("Cargo.toml");
+        write_file(&version_file, b"[package]\nversion = \"2.1.0\"\n")?;
+        let path = version_file.to_str().unwrap();
+        test_ok(vec!["bump", "set", "-w", "-f", path, "3.0.0"], "3.0.0")?;
+        assert_eq!(
+            b"[package]\nversion = \"3.0.0\"\n",
+            read_file(&version_file)?.as_slice()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_ng() -> Result<()> {
+        test_err(vec!["bump", "set", "2.0.0", "2.1.0"])?;
+        test_err(vec!["bump", "set", "x.y.z", "2.1.0"])?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn compare_ok() -> Result<()> {
+        test_ok(vec!["bump", "compare", "1.0.0", "1.0.1"], "<")?;
+        test_ok(vec!["bump", "compare", "1.0.0+a", "1.0.0+b"], "=")?;
+        test_ok(vec!["bump", "compare", "v1.0.0", "1.0.0-rc.1"], ">")?;
+
+        let args = Args::try_parse_from(["bump", "compare", "--expect", "gt", "1.0.0", "1.0.1"])?;
+        assert_eq!(EXIT_CODE_MISMATCH, run(args)?.code);
+        let args = Args::try_parse_from(["bump", "compare", "--expect", "le", "1.0.0", "1.0.1"])?;
+        assert_eq!(EXIT_CODE_OK, run(args)?.code);
+
+        test_err(vec!["bump", "compare", "1.0", "1.0.1"])?;
+        Ok(())
+    }
+
+    #[test]
+    fn sort_ok() -> Result<()> {
+        let lines = "1.10.0\nv1.2.0\n1.2.0\n\n1.2.0-rc.1\nv1.10.0\n  1.9.0+build\n";
+        assert_eq!(
+            "1.2.0-rc.1\n1.2.0\n1.9.0+build\n1.10.0\nv1.2.0\nv1.10.0",
+            sort(lines, false, io::sink())?
+        );
+        assert_eq!(
+            "v1.10.0\nv1.2.0\n1.10.0\n1.9.0+build\n1.2.0\n1.2.0-rc.1",
+            sort(lines, true, io::sink())?
+        );
+        assert_eq!("", sort("", false, io::sink())?);
+        Ok(())
+    }
+
+    #[test]
+    fn sort_skip_ok() -> Result<()> {
+        let mut errors = vec![];
+        assert_eq!(
+            "1.0.0\nv1.1.0",
+            sort("v1.1.0\nlatest\n1.0.0\n\nx.y.z\n", false, &mut errors)?
+        );
+        let errors = String::from_utf8(errors)?;
+        assert_eq!(2, errors.lines().count());
+        assert!(errors.starts_with("skipped line 2: "), "{errors}");
+        assert!(errors.contains("skipped line 5: "), "{errors}");
+        Ok(())
+    }
+
+    #[test]
+    fn satisfies_ok() -> Result<()> {
+        test_ok(vec!["bump", "satisfies", "^1.2", "v1.4.0"], "true")?;
+        test_ok(vec!["bump", "satisfies", ">=1.4, <2", "1.3.9"], "false")?;
+        test_ok(
+            vec!["bump", "satisfies", ">=1.4, <2", "2.0.0-rc.1"],
+            "false",
+        )?;
+
+        let args = Args::try_parse_from(["bump", "satisfies", "~1.2", "1.3.0"])?;
+        assert_eq!(EXIT_CODE_MISMATCH, run(args)?.code);
+
+        test_err(vec!["bump", "satisfies", "^x", "1.0.0"])?;
+        Ok(())
+    }
+
+    #[test]
+    fn max_satisfying_ok() -> Result<()> {
+        let lines = "v1.2.0\nv1.10.0\nv1.3.0-rc.1\nv2.0.0\nv1.9.3\n";
+        let req = VersionReq::parse("^1.2")?;
+        assert_eq!(Some("v1.10.0"), max_satisfying(&req, lines, io::sink())?);
+        let req = VersionReq::parse(">=1.2, <1.10")?;
+        assert_eq!(Some("v1.9.3"), max_satisfying(&req, lines, io::sink())?);
+        let req = VersionReq::parse("^3")?;
+        assert_eq!(None, max_satisfying(&req, lines, io::sink())?);
+        let req = VersionReq::parse("^1")?;
+        assert_eq!(
+            Some("1.0.0"),
+            max_satisfying(&req, "1.0.0\nlatest\n", io::sink())?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn validate_ok() -> Result<()> {
+        test_ok(vec!["bump", "validate", "v1.2.3-rc.1"], "v1.2.3-rc.1")?;
+        test_ok(
+            vec!["bump", "validate", "v1.02.3"],
+            "Leading zero in minor version at byte 3\nv1.02.3\n   ^",
+        )?;
+        test_ok(
+            vec!["bump", "validate", "--output", "json", "1.0.0-rc..1"],
+            r#"{"input":"1.0.0-rc..1","kind":"empty-identifier","message":"Empty pre-release identifier","offset":9,"valid":false}"#,
+        )?;
+        test_ok(
+            vec!["bump", "validate", "--output", "json", "1.0.0"],
+            r#"{"input":"1.0.0","valid":true}"#,
+        )?;
+
+        let args = Args::try_parse_from(["bump", "validate", "1.2"])?;
+        assert_eq!(EXIT_CODE_MISMATCH, run(args)?.code);
+        Ok(())
+    }
+
+    #[test]
+    fn output_json_ok() -> Result<()> {
+        test_ok(
+            vec!["bump", "minor", "--output", "json", "v1.2.3-rc.1+build"],
+            r#"{"build":null,"bumped_part":"minor","major":1,"minor":3,"patch":0,"pre":null,"prefix":"v","previous":"v1.2.3-rc.1+build","suffix":null}"#,
+        )?;
+        test_ok(
+            vec!["bump", "pre", "--output", "json", "rc.2", "1.0.0-rc.1+b.1"],
+            r#"{"build":"b.1","bumped_part":"pre","major":1,"minor":0,"patch":0,"pre":"rc.2","prefix":"","previous":"1.0.0-rc.1+b.1","suffix":null}"#,
+        )?;
+        test_ok(
+            vec!["bump", "release", "--output", "json", "1.0.0"],
+            r#"{"build":null,"bumped_part":null,"major":1,"minor":0,"patch":0,"pre":null,"prefix":"","previous":"1.0.0","suffix":null}"#,
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn format_ok() -> Result<()> {
+        test_ok(
+            vec!["bump", "minor", "--format", "{major}.{minor}", "v1.3.9"],
+            "1.4",
+        )?;
+        test_ok(
+            vec!["bump", "major", "--format", "{prefix}{major}", "v1.3.9"],
+            "v2",
+        )?;
+        test_ok(vec!["bump", "major", "--format", "{pre}", "v1.3.9"], "")?;
+        test_err(vec!["bump", "major", "--format", "{micro}", "v1.3.9"])?;
+        Ok(())
+    }
+
+    #[test]
+    fn get_ok() -> Result<()> {
+        test_ok(vec!["bump", "get", "minor", "v1.4.2-rc.1"], "4")?;
+        test_ok(vec!["bump", "get", "prefix", "v1.4.2-rc.1"], "v")?;
+        test_ok(vec!["bump", "get", "pre", "v1.4.2-rc.1"], "rc.1")?;
+        test_ok(vec!["bump", "get", "build", "v1.4.2-rc.1"], "")?;
+
+        let tmp_dir = tempdir()?;
+        let path = tmp_dir.path().join("Cargo.toml");
+        write_file(&path, b"[package]\nversion = \"0.3.1\"\n")?;
+        test_ok(
+            vec!["bump", "get", "patch", "-f", path.to_str().unwrap()],
+            "1",
+        )?;
+        Ok(())
+    }
+
+    fn test_lines(row_args: Vec<&str>, lines: &str) -> (Result<i32>, String, String) {
+        let args = Args::try_parse_from(row_args).unwrap();
+        let (input, _) = args.sub.input().unwrap();
+        let (mut out, mut err) = (vec![], vec![]);
+        let result = bump_lines(&args.sub, input, lines.as_bytes(), &mut out, &mut err);
+        let out = String::from_utf8(out).unwrap();
+        let err = String::from_utf8(err).unwrap();
+        (result, out, err)
+    }
+
+    #[test]
+    fn lines_ok() {
+        let lines = "api-1.0.0\n\nweb-2.3.9-rc.1\n  v0.1.0  \n";
+        let (result, out, err) = test_lines(vec!["bump", "minor", "--lines"], lines);
+        assert!(result.is_ok());
+        assert_eq!("api-1.1.0\nweb-2.4.0\nv0.2.0\n", out);
+        assert_eq!("", err);
+
+        let args = vec![
+            "bump",
+            "patch",
+            "--lines",
+            "--format",
+            "{major}.{minor}.{patch}",
+        ];
+        let (_, out, _) = test_lines(args, "v1.0.0\nv2.0.0\n");
+        assert_eq!("1.0.1\n2.0.1\n", out);
+    }
+
+    #[test]
+    fn lines_ng() {
+        let lines = "1.0.0\nlatest\n2.0.0\n3.0\n";
+        let (result, out, err) = test_lines(vec!["bump", "patch", "--lines"], lines);
+        assert_eq!(
+            "line 2: Can't find semver format. value: latest",
+            format!("{:#}", result.unwrap_err())
+        );
+        assert_eq!("1.0.1\n", out);
+        assert_eq!("", err);
+
+        let args = vec!["bump", "patch", "--lines", "--keep-going"];
+        let (result, out, err) = test_lines(args, lines);
+        assert_eq!(EXIT_CODE_PARSE, result.unwrap());
+        assert_eq!("1.0.1\n2.0.1\n", out);
+        assert_eq!(
+            "line 2: Can't find semver format. value: latest\nline 4: Can't find semver format. value: 3.0\n2 line(s) failed\n",
+            err
+        );
+
+        // The first failure decides the exit code
+        let args = vec!["bump", "major", "--lines", "--keep-going"];
+        let (result, _, _) = test_lines(args.clone(), "18446744073709551615.0.0\nlatest\n");
+        assert_eq!(EXIT_CODE_OVERFLOW, result.unwrap());
+        let (result, _, _) = test_lines(args, "latest\n18446744073709551615.0.0\n");
+        assert_eq!(EXIT_CODE_PARSE, result.unwrap());
+    }
+
+    #[test]
+    fn lines_warning_ok() {
+        let (result, out, err) =
+            test_lines(vec!["bump", "patch", "--lines"], "1.0.0-linux-amd64\n");
+        assert!(result.is_ok());
+        assert_eq!("1.0.1\n", out);
+        assert!(err.starts_with("warning: dropped the pre-release"), "{err}");
+    }
+
+    #[test]
+    fn lines_args_ng() {
+        let inputs = vec![
+            vec!["bump", "patch", "--lines", "1.0.0"],
+            vec!["bump", "patch", "--lines", "--file", "VERSION"],
+            vec!["bump", "patch", "--lines", "--write"],
+            vec!["bump", "patch", "--keep-going"],
+        ];
+        for args in inputs {
+            assert!(Args::try_parse_from(&args).is_err(), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn exit_code_ok() -> Result<()> {
+        let inputs = vec![
+            (vec!["bump", "patch", "1.0"], EXIT_CODE_PARSE),
+            (vec!["bump", "pre", "rc..1", "1.0.0"], EXIT_CODE_INVALID_PRE),
+            (
+                vec!["bump", "pre", "--next", "rc.1", "1.0.0"],
+                EXIT_CODE_INVALID_PRE,
+            ),
+            (
+                vec!["bump", "build", "a+b", "1.0.0"],
+                EXIT_CODE_INVALID_BUILD,
+            ),
+            (
+                vec![
+                    "bump",
+                    "pre",
+                    "--next",
+                    "rc",
+                    "1.0.0-rc.18446744073709551615",
+                ],
+                EXIT_CODE_OVERFLOW,
+            ),
+            (vec!["bump", "patch", "-f", "not_found"], EXIT_CODE_IO),
+            (vec!["bump", "set", "1.0.0", "2.0.0"], EXIT_CODE_DOWNGRADE),
+            (vec!["bump", "patch", "-w", "-f", "-"], EXIT_CODE_USAGE),
+            (
+                vec!["bump", "patch", "--format", "{x}", "1.0.0"],
+                EXIT_CODE_USAGE,
+            ),
+        ];
+        for (args, expect) in inputs {
+            let err = run(Args::try_parse_from(&args)?).unwrap_err();
+            assert_eq!(expect, exit_code(&err), "{args:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn prefix_option_ok() -> Result<()> {
+        test_ok(vec!["bump", "patch", "--prefix", "v", "1.0.0"], "v1.0.1")?;
+        test_ok(
+            vec!["bump", "patch", "--prefix", "release-", "v1.0.0"],
+            "release-1.0.1",
+        )?;
+        test_ok(vec!["bump", "patch", "--strip-prefix", "v1.0.0"], "1.0.1")?;
+        test_ok(
+            vec![
+                "bump",
+                "patch",
+                "--allowed-prefixes",
+                "v,release-",
+                "release-1.0.0",
+            ],
+            "release-1.0.1",
+        )?;
+        test_ok(
+            vec!["bump", "patch", "--allowed-prefixes", "v", "1.0.0"],
+            "1.0.1",
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn prefix_option_ng() -> Result<()> {
+        test_err(vec![
+            "bump",
+            "patch",
+            "--allowed-prefixes",
+            "v",
+            "build 1.0.0",
+        ])?;
+        test_err(vec!["bump", "patch", "--prefix", "app1", "1.0.0"])?;
+        let inputs = vec![
+            vec!["bump", "patch", "--prefix", "v", "--strip-prefix"],
+            // Files keep their own prefix, which would differ from the printed one
+            vec!["bump", "minor", "-w", "--strip-prefix", "-f", "VERSION"],
+            vec!["bump", "patch", "-n", "--prefix", "v", "-f", "Cargo.toml"],
+        ];
+        for args in inputs {
+            assert!(Args::try_parse_from(&args).is_err(), "{args:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn suffix_ok() -> Result<()> {
+        test_ok(
+            vec!["bump", "minor", "app-1.2.3.tar.gz"],
+            "app-1.3.0.tar.gz",
+        )?;
+        test_ok(
+            vec!["bump", "patch", "--known-suffixes", ".4", "1.2.3.4"],
+            "1.2.4.4",
+        )?;
+        test_ok(
+            vec![
+                "bump",
+                "patch",
+                "--known-suffixes",
+                "-linux-amd64,.tar.gz",
+                "chart-1.2.3-linux-amd64",
+            ],
+            "chart-1.2.4-linux-amd64",
+        )?;
+        test_ok(vec!["bump", "get", "suffix", "app-1.2.3.tar.gz"], ".tar.gz")?;
+
+        let tmp_dir = tempdir()?;
+        let path = tmp_dir.path().join("VERSION");
+        write_file(&path, b"1.2.3.4\n")?;
+        let file = path.to_str().unwrap();
+        test_ok(
+            vec!["bump", "patch", "--known-suffixes", ".4", "-w", "-f", file],
+            "1.2.4.4",
+        )?;
+        assert_eq!(b"1.2.4.4\n", read_file(&path)?.as_slice());
+
+        let config = tmp_dir.path().join(".bump.toml");
+        write_file(&config, b"[[file]]\npath = \"VERSION\"\n")?;
+        let config = config.to_str().unwrap();
+        test_ok(
+            vec!["bump", "minor", "--known-suffixes", ".4", "-c", config],
+            "1.3.0.4",
+        )?;
+        Ok(())
+    }
+
     #[test]
     fn remove_build_ok() -> Result<()> {
         let version = "1.0.0+20190722";
@@ -267,6 +1191,18 @@ mod tests {
         let args = vec!["bump", "patch", version];
         test_err(args)?;
 
+        let version = "00.0.0";
+        let args = vec!["bump", "patch", version];
+        test_err(args)?;
+
+        let version = "1.2.3.4";
+        let args = vec!["bump", "patch", version];
+        test_err(args)?;
+
+        let version = "1.0.0-rc.01";
+        let args = vec!["bump", "patch", version];
+        test_err(args)?;
+
         Ok(())
     }
 
@@ -292,4 +1228,145 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn write_ok() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let version_file = tmp_dir.path().join("version.txt");
+        write_file(&version_file, b"v0.1.0\n")?;
+        let path = version_file.to_str().unwrap();
+
+        test_ok(vec!["bump", "patch", "-w", "-f", path], "v0.1.1")?;
+        test_ok(vec!["bump", "minor", "--write", "-f", path], "v0.2.0")?;
+        test_ok(vec!["bump", "pre", "-w", "-f", path, "rc.0"], "v0.2.0-rc.0")?;
+        assert_eq!(b"v0.2.0-rc.0\n", read_file(&version_file)?.as_slice());
+
+        Ok(())
+    }
+
+    #[test]
+    fn pattern_ok() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let version_file = tmp_dir.path().join("main.rs");
+        let before = "// 0.0.1\nconst VERSION: &str = \"v1.2.3\";\n";
+        write_file(&version_file, before.as_bytes())?;
+        let path = version_file.to_str().unwrap();
+        let pattern = r#"VERSION: &str = "(?P<version>[^"]+)""#;
+
+        test_ok(vec!["bump", "minor", "-f", path, "-p", pattern], "v1.3.0")?;
+        test_ok(
+            vec!["bump", "major", "-w", "-f", path, "--pattern", pattern],
+            "v2.0.0",
+        )?;
+        let after = "// 0.0.1\nconst VERSION: &str = \"v2.0.0\";\n";
+        assert_eq!(after.as_bytes(), read_file(&version_file)?.as_slice());
+
+        Ok(())
+    }
+
+    #[test]
+    fn pattern_ng() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let version_file = tmp_dir.path().join("main.rs");
+        write_file(&version_file, b"const VERSION: &str = \"1.2.3\";\n")?;
+        let path = version_file.to_str().unwrap();
+
+        test_err(vec!["bump", "patch", "-f", path, "-p", r"(\d+\.\d+\.\d+)"])?;
+        test_err(vec![
+            "bump",
+            "patch",
+            "-f",
+            path,
+            "-p",
+            r"NAME=(?P<version>\S+)",
+        ])?;
+        test_err(vec!["bump", "patch", "-f", "-", "-p", r"(?P<version>\S+)"])?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn config_ok() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let dir = tmp_dir.path();
+        let config = "[[file]]\npath = \"VERSION\"\n\n[[file]]\npath = \"package.json\"\n";
+        write_file(dir.join(".bump.toml"), config.as_bytes())?;
+        write_file(dir.join("VERSION"), b"v1.0.0\n")?;
+        write_file(dir.join("package.json"), b"{\"version\": \"1.0.0\"}\n")?;
+        let path = dir.join(".bump.toml");
+
+        test_ok(
+            vec!["bump", "patch", "-c", path.to_str().unwrap()],
+            "v1.0.1",
+        )?;
+        assert_eq!(b"v1.0.0\n", read_file(dir.join("VERSION"))?.as_slice());
+
+        test_ok(
+            vec!["bump", "patch", "-w", "-c", path.to_str().unwrap()],
+            "v1.0.1",
+        )?;
+        assert_eq!(b"v1.0.1\n", read_file(dir.join("VERSION"))?.as_slice());
+        assert_eq!(
+            b"{\"version\": \"1.0.1\"}\n",
+            read_file(dir.join("package.json"))?.as_slice()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn config_inconsistent_ng() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let dir = tmp_dir.path();
+        let config = "[[file]]\npath = \"VERSION\"\n\n[[file]]\npath = \"package.json\"\n";
+        write_file(dir.join(".bump.toml"), config.as_bytes())?;
+        write_file(dir.join("VERSION"), b"1.0.0\n")?;
+        write_file(dir.join("package.json"), b"{\"version\": \"0.9.0\"}\n")?;
+        let path = dir.join(".bump.toml");
+
+        test_err(vec!["bump", "patch", "-c", path.to_str().unwrap()])?;
+        assert_eq!(b"1.0.0\n", read_file(dir.join("VERSION"))?.as_slice());
+
+        let args = vec!["bump", "patch", "-c", path.to_str().unwrap(), "1.0.0"];
+        assert!(Args::try_parse_from(args).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn dry_run_ok() -> Result<()> {
+        let tmp_dir = tempdir()?;
+        let version_file = tmp_dir.path().join("version.txt");
+        write_file(&version_file, b"1.0.0\n")?;
+        let path = version_file.to_str().unwrap();
+
+        let args = Args::try_parse_from(vec!["bump", "patch", "-n", "-f", path])?;
+        let expect = format!("--- a/{path}\n+++ b/{path}\n@@ -1 +1 @@\n-1.0.0\n+1.0.1\n1.0.1");
+        let expect = Output {
+            view: Some(expect),
+            code: EXIT_CODE_CHANGED,
+        };
+        assert_eq!(expect, run(args)?);
+        assert_eq!(b"1.0.0\n", read_file(&version_file)?.as_slice());
+
+        let args = Args::try_parse_from(vec!["bump", "build", "", "--dry-run", "-w", "-f", path])?;
+        let expect = Output {
+            view: Some(String::from("1.0.0")),
+            code: EXIT_CODE_OK,
+        };
+        assert_eq!(expect, run(args)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_without_file_ng() -> Result<()> {
+        let args = vec!["bump", "patch", "-w", "1.0.0"];
+        assert!(Args::try_parse_from(args).is_err());
+
+        let args = vec!["bump", "patch", "-w", "-f", "-"];
+        test_err(args)?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,253 @@
+use super::{keys, VersionSource};
+use anyhow::{anyhow, bail, Result};
+use std::ops::Range;
+
+/// A string value in a JSON document, found at every one of `keys` that exists.
+///
+/// Keys are dotted paths, so `packages..version` is the `version` of the `""` entry of
+/// `packages`. The document is only scanned, never rebuilt, so its formatting is kept as is.
+#[derive(Debug, Clone)]
+pub struct Json {
+    keys: Vec<String>,
+}
+
+impl Json {
+    pub fn new<I, S>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Json {
+            keys: keys.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// npm's `package.json`.
+    pub fn package() -> Self {
+        Json::new(["version"])
+    }
+
+    /// npm's `package-lock.json`, which repeats the version under `packages[""]` since
+    /// lockfileVersion 2.
+    pub fn package_lock() -> Self {
+        Json::new(["version", "packages..version"])
+    }
+}
+
+impl VersionSource for Json {
+    fn locate(&self, buf: &[u8]) -> Result<Vec<Range<usize>>> {
+        let mut spans = Vec::new();
+        for key in &self.keys {
+            let keys = keys(key).collect::<Vec<_>>();
+            if let Some(span) = locate(buf, &keys)? {
+                spans.push(span);
+            }
+        }
+        if spans.is_empty() {
+            bail!("Can't find version field. keys: {}", self.keys.join(", "));
+        }
+        spans.sort_by_key(|span| span.start);
+        Ok(spans)
+    }
+}
+
+/// Returns the span of the string value found at `keys`, without its quotes.
+fn locate(buf: &[u8], keys: &[&str]) -> Result<Option<Range<usize>>> {
+    let mut scanner = Scanner { buf, pos: 0 };
+    let span = scanner.value(Some(keys))?;
+    scanner.skip_ws();
+    if scanner.pos != scanner.buf.len() {
+        bail!("Invalid JSON, unexpected character at {}", scanner.pos);
+    }
+    Ok(span)
+}
+
+struct Scanner<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl Scanner<'_> {
+    /// Skips one value; `keys` is the remaining path to follow, or `None` when nothing below this
+    /// value is wanted.
+    fn value(&mut self, keys: Option<&[&str]>) -> Result<Option<Range<usize>>> {
+        self.skip_ws();
+        match self.peek()? {
+            b'{' => self.object(keys),
+            b'[' => self.array(),
+            b'"' => {
+                let span = self.string()?;
+                Ok(keys.filter(|k| k.is_empty()).map(|_| span))
+            }
+            _ => self.literal(),
+        }
+    }
+
+    fn object(&mut self, keys: Option<&[&str]>) -> Result<Option<Range<usize>>> {
+        self.expect(b'{')?;
+        let mut found = None;
+        self.skip_ws();
+        if self.peek()? == b'}' {
+            self.pos += 1;
+            return Ok(None);
+        }
+        loop {
+            self.skip_ws();
+            let key = self.string()?;
+            let key = &self.buf[key];
+            self.skip_ws();
+            self.expect(b':')?;
+
+            let next = match keys {
+                Some([first, rest @ ..]) if found.is_none() && key == first.as_bytes() => {
+                    Some(rest)
+                }
+                _ => None,
+            };
+            if let Some(span) = self.value(next)? {
+                found = Some(span);
+            }
+
+            self.skip_ws();
+            match self.next()? {
+                b',' => continue,
+                b'}' => return Ok(found),
+                c => bail!(
+                    "Invalid JSON, unexpected '{}' at {}",
+                    c as char,
+                    self.pos - 1
+                ),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Option<Range<usize>>> {
+        self.expect(b'[')?;
+        self.skip_ws();
+        if self.peek()? == b']' {
+            self.pos += 1;
+            return Ok(None);
+        }
+        loop {
+            self.value(None)?;
+            self.skip_ws();
+            match self.next()? {
+                b',' => continue,
+                b']' => return Ok(None),
+                c => bail!(
+                    "Invalid JSON, unexpected '{}' at {}",
+                    c as char,
+                    self.pos - 1
+                ),
+            }
+        }
+    }
+
+    /// Returns the span between the quotes.
+    fn string(&mut self) -> Result<Range<usize>> {
+        self.expect(b'"')?;
+        let start = self.pos;
+        loop {
+            match self.next()? {
+                b'\\' => self.pos += 1,
+                b'"' => return Ok(start..self.pos - 1),
+                _ => {}
+            }
+        }
+    }
+
+    fn literal(&mut self) -> Result<Option<Range<usize>>> {
+        let start = self.pos;
+        while self
+            .buf
+            .get(self.pos)
+            .is_some_and(|c| c.is_ascii_alphanumeric() || b"+-.".contains(c))
+        {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            bail!("Invalid JSON, unexpected character at {}", start);
+        }
+        Ok(None)
+    }
+
+    fn skip_ws(&mut self) {
+        while self.buf.get(self.pos).is_some_and(u8::is_ascii_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Result<u8> {
+        self.buf
+            .get(self.pos)
+            .copied()
+            .ok_or_else(|| anyhow!("Invalid JSON, unexpected end of input"))
+    }
+
+    fn next(&mut self) -> Result<u8> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Ok(c)
+    }
+
+    fn expect(&mut self, c: u8) -> Result<()> {
+        match self.next()? {
+            n if n == c => Ok(()),
+            n => bail!(
+                "Invalid JSON, expected '{}' but found '{}' at {}",
+                c as char,
+                n as char,
+                self.pos - 1
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn located<'a>(source: &Json, contents: &'a str) -> Result<Vec<&'a str>> {
+        let spans = source.locate(contents.as_bytes())?;
+        Ok(spans.into_iter().map(|span| &contents[span]).collect())
+    }
+
+    #[test]
+    fn locate_ok() -> Result<()> {
+        let contents = r#"{
+  "name": "app",
+  "scripts": { "version": "echo \"1\"" },
+  "keywords": ["a", 1, -2.5e3, true, null, {}],
+  "version": "1.2.3"
+}
+"#;
+        assert_eq!(vec!["1.2.3"], located(&Json::package(), contents)?);
+        Ok(())
+    }
+
+    #[test]
+    fn locate_package_lock_ok() -> Result<()> {
+        let contents = r#"{"name":"app","version":"1.0.0","packages":{"a":{"version":"9.9.9"},"":{"version":"1.0.1"}}}"#;
+        let actual = located(&Json::package_lock(), contents)?;
+        assert_eq!(vec!["1.0.0", "1.0.1"], actual);
+
+        let contents = r#"{"name":"app","version":"1.0.0","lockfileVersion":1}"#;
+        assert_eq!(vec!["1.0.0"], located(&Json::package_lock(), contents)?);
+        Ok(())
+    }
+
+    #[test]
+    fn locate_ng() {
+        let inputs = vec![
+            r#"{"name": "app"}"#,
+            r#"{"dependencies": {"version": "1.0.0"}}"#,
+            r#"{"version": 1}"#,
+            r#"{"version": "1.0.0""#,
+            r#"{"version": "1.0.0"} x"#,
+            r#"["version", "1.0.0"]"#,
+        ];
+        for input in inputs {
+            assert!(located(&Json::package(), input).is_err(), "{input}");
+        }
+    }
+}
@@ -0,0 +1,148 @@
+mod json;
+mod pattern;
+mod text;
+mod toml;
+mod yaml;
+
+pub use self::json::Json;
+pub use self::pattern::Pattern;
+pub use self::text::Text;
+pub use self::toml::Toml;
+pub use self::yaml::Yaml;
+
+use crate::version::Version;
+use anyhow::{bail, Result};
+use std::ops::Range;
+use std::path::Path;
+use std::str;
+
+/// A file format that carries a version somewhere inside it.
+///
+/// Implementors only have to find the version; reading and replacing it are derived from the
+/// spans returned by [`VersionSource::locate`], so that everything outside of them is kept as is.
+pub trait VersionSource {
+    /// Returns the byte spans of every copy of the version within `buf`.
+    ///
+    /// The returned list must not be empty, and the spans must be sorted and must not overlap.
+    fn locate(&self, buf: &[u8]) -> Result<Vec<Range<usize>>>;
+
+    /// Reads the version, failing if its copies disagree.
+    fn read(&self, buf: &[u8]) -> Result<Version> {
+        self.read_with_suffixes(buf, &[])
+    }
+
+    /// Reads the version like [`VersionSource::read`], keeping the suffixes of `known` as is, see
+    /// [`Version::parse_with_suffixes`].
+    fn read_with_suffixes(&self, buf: &[u8], known: &[String]) -> Result<Version> {
+        let spans = self.locate(buf)?;
+        let Some((first, rest)) = spans.split_first() else {
+            bail!("Can't find version field");
+        };
+        let raw = &buf[first.clone()];
+        if let Some(span) = rest.iter().find(|span| &buf[(*span).clone()] != raw) {
+            bail!(
+                "Inconsistent versions. {} and {}",
+                String::from_utf8_lossy(raw),
+                String::from_utf8_lossy(&buf[span.clone()])
+            );
+        }
+        Ok(Version::parse_with_suffixes(str::from_utf8(raw)?, known)?)
+    }
+
+    /// Returns a copy of `buf` with every copy of the version replaced by `version`.
+    fn replace(&self, buf: &[u8], version: &Version) -> Result<Vec<u8>> {
+        let version = version.to_string();
+        let mut out = Vec::with_capacity(buf.len() + version.len());
+        let mut pos = 0;
+        for span in self.locate(buf)? {
+            out.extend_from_slice(&buf[pos..span.start]);
+            out.extend_from_slice(version.as_bytes());
+            pos = span.end;
+        }
+        out.extend_from_slice(&buf[pos..]);
+        Ok(out)
+    }
+}
+
+/// Splits a dotted key path such as `package.version`.
+fn keys(path: &str) -> impl Iterator<Item = &str> {
+    path.split('.')
+}
+
+/// Picks the built-in source for a file from its name, falling back to [`Text`].
+pub fn detect(path: impl AsRef<Path>) -> Box<dyn VersionSource> {
+    let path = path.as_ref();
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default();
+    match (name, ext) {
+        ("Cargo.toml", _) => Box::new(Toml::cargo()),
+        ("package.json", _) => Box::new(Json::package()),
+        ("package-lock.json" | "npm-shrinkwrap.json", _) => Box::new(Json::package_lock()),
+        (_, "yaml" | "yml") => Box::new(Yaml::default()),
+        _ => Box::new(Text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An in-house format where the version sits between `<v>` and `</v>`.
+    struct Tag;
+
+    impl VersionSource for Tag {
+        fn locate(&self, buf: &[u8]) -> Result<Vec<Range<usize>>> {
+            let s = str::from_utf8(buf)?;
+            let spans = s
+                .match_indices("<v>")
+                .filter_map(|(i, _)| {
+                    let start = i + 3;
+                    s[start..].find("</v>").map(|len| start..start + len)
+                })
+                .collect::<Vec<_>>();
+            if spans.is_empty() {
+                bail!("Can't find version field");
+            }
+            Ok(spans)
+        }
+    }
+
+    #[test]
+    fn custom_source_ok() -> Result<()> {
+        let buf = b"<app><v>1.0.0</v><v>1.0.0</v></app>";
+        let version = Tag.read(buf)?;
+        assert_eq!("1.0.0", version.to_string());
+
+        let actual = Tag.replace(buf, &version.bump_major()?)?;
+        assert_eq!(b"<app><v>2.0.0</v><v>2.0.0</v></app>", actual.as_slice());
+        Ok(())
+    }
+
+    #[test]
+    fn custom_source_ng() {
+        assert!(Tag.read(b"<app></app>").is_err());
+        assert!(Tag.read(b"<v>1.0.0</v><v>1.0.1</v>").is_err());
+    }
+
+    #[test]
+    fn detect_ok() -> Result<()> {
+        let inputs = vec![
+            ("Cargo.toml", "[package]\nversion = \"1.0.0\"\n"),
+            ("dir/package.json", "{\"version\": \"1.0.0\"}"),
+            ("package-lock.json", "{\"version\": \"1.0.0\"}"),
+            ("Chart.yaml", "name: app\nversion: 1.0.0\n"),
+            ("VERSION", "1.0.0\n"),
+        ];
+        for (path, buf) in inputs {
+            let version = detect(path).read(buf.as_bytes())?;
+            assert_eq!("1.0.0", version.to_string(), "{path}");
+        }
+        Ok(())
+    }
+}
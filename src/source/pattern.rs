@@ -0,0 +1,74 @@
+use super::VersionSource;
+use anyhow::{bail, Result};
+use regex::bytes::Regex;
+use std::ops::Range;
+
+/// Every match of a regular expression, where the capture group named `version` is the version.
+///
+/// ```
+/// use bump_bin::source::{Pattern, VersionSource};
+///
+/// let source = Pattern::new(r#"const VERSION: &str = "(?P<version>[^"]+)";"#).unwrap();
+/// let version = source.read(br#"const VERSION: &str = "1.2.3";"#).unwrap();
+/// assert_eq!("1.2.3", version.to_string());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    regex: Regex,
+}
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Result<Self> {
+        let regex = Regex::new(pattern)?;
+        if !regex.capture_names().any(|name| name == Some("version")) {
+            bail!("Pattern needs a capture group named `version`. pattern: {pattern}");
+        }
+        Ok(Pattern { regex })
+    }
+}
+
+impl VersionSource for Pattern {
+    fn locate(&self, buf: &[u8]) -> Result<Vec<Range<usize>>> {
+        let spans = self
+            .regex
+            .captures_iter(buf)
+            .filter_map(|caps| caps.name("version"))
+            .map(|m| m.range())
+            .collect::<Vec<_>>();
+        if spans.is_empty() {
+            bail!("Can't find version. pattern: {}", self.regex);
+        }
+        Ok(spans)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_ng() {
+        assert!(Pattern::new(r"(\d+\.\d+\.\d+)").is_err());
+        assert!(Pattern::new(r"(?P<version>").is_err());
+    }
+
+    #[test]
+    fn replace_ok() -> Result<()> {
+        let buf = b"FROM alpine\nLABEL version=v1.2.3\nLABEL other=1.2.3\n";
+        let source = Pattern::new(r"LABEL version=(?P<version>\S+)")?;
+
+        let actual = source.replace(buf, &source.read(buf)?.bump_minor()?)?;
+        assert_eq!(
+            b"FROM alpine\nLABEL version=v1.3.0\nLABEL other=1.2.3\n",
+            actual.as_slice()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn locate_ng() -> Result<()> {
+        let source = Pattern::new(r"version=(?P<version>\S+)")?;
+        assert!(source.locate(b"name=app\n").is_err());
+        Ok(())
+    }
+}
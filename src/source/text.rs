@@ -0,0 +1,40 @@
+use super::VersionSource;
+use anyhow::Result;
+use std::ops::Range;
+
+/// A file holding nothing but the version, like a `VERSION` file.
+///
+/// Trailing whitespace is not part of the version, so a final newline survives a rewrite. The
+/// version may have a prefix and a suffix on its line, but a file of several lines is an error.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Text;
+
+impl VersionSource for Text {
+    fn locate(&self, buf: &[u8]) -> Result<Vec<Range<usize>>> {
+        let len = buf.trim_ascii_end().len();
+        Ok(vec![Range { start: 0, end: len }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replace_ok() -> Result<()> {
+        let actual = Text.replace(b"v1.0.0\n", &Text.read(b"v1.0.0\n")?.bump_patch()?)?;
+        assert_eq!(b"v1.0.1\n", actual.as_slice());
+        Ok(())
+    }
+
+    #[test]
+    fn read_ng() {
+        let inputs = vec![
+            "1.0.0\n1.0.1\n",
+            "requires = [\"setuptools>=61.0.0\"]\nversion = \"1.2.3\"\n",
+        ];
+        for input in inputs {
+            assert!(Text.read(input.as_bytes()).is_err(), "{input}");
+        }
+    }
+}
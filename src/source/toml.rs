@@ -0,0 +1,124 @@
+use super::{keys, VersionSource};
+use anyhow::{anyhow, Result};
+use std::ops::Range;
+use std::str;
+use toml_edit::{Document, Item};
+
+/// A string value in a TOML document, found at the first of `keys` that holds one.
+///
+/// Keys are dotted paths such as `package.version`.
+#[derive(Debug, Clone)]
+pub struct Toml {
+    keys: Vec<String>,
+}
+
+impl Toml {
+    pub fn new<I, S>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Toml {
+            keys: keys.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// `Cargo.toml`, where a crate's own version wins over the workspace one.
+    pub fn cargo() -> Self {
+        Toml::new(["package.version", "workspace.package.version"])
+    }
+}
+
+impl VersionSource for Toml {
+    fn locate(&self, buf: &[u8]) -> Result<Vec<Range<usize>>> {
+        let contents = str::from_utf8(buf)?;
+        let doc = Document::parse(contents)?;
+
+        self.keys
+            .iter()
+            .find_map(|key| {
+                let item = keys(key).try_fold(doc.as_item(), |item, key| item.get(key))?;
+                string_span(contents, item)
+            })
+            .map(|span| vec![span])
+            .ok_or_else(|| anyhow!("Can't find version field. keys: {}", self.keys.join(", ")))
+    }
+}
+
+/// Returns the span of the string value without its quotes.
+fn string_span(contents: &str, item: &Item) -> Option<Range<usize>> {
+    let value = item.as_value()?;
+    let s = value.as_str()?;
+    let span = value.span()?;
+    let offset = contents[span.clone()].find(s)?;
+    let start = span.start + offset;
+    Some(start..start + s.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn located(contents: &str) -> Result<&str> {
+        let spans = Toml::cargo().locate(contents.as_bytes())?;
+        Ok(&contents[spans[0].clone()])
+    }
+
+    #[test]
+    fn locate_package_ok() -> Result<()> {
+        let contents = r#"
+# comment
+[package]
+name = "bump"
+version = "0.4.3" # inline
+
+[dependencies]
+semver = { version = "1" }
+"#;
+        assert_eq!("0.4.3", located(contents)?);
+        Ok(())
+    }
+
+    #[test]
+    fn locate_workspace_ok() -> Result<()> {
+        let contents = r#"
+[workspace]
+members = ["a"]
+
+[workspace.package]
+version = '1.0.0-rc.1'
+"#;
+        assert_eq!("1.0.0-rc.1", located(contents)?);
+
+        let contents = r#"
+[package]
+name = "a"
+version.workspace = true
+
+[workspace.package]
+version = "2.0.0"
+"#;
+        assert_eq!("2.0.0", located(contents)?);
+        Ok(())
+    }
+
+    #[test]
+    fn locate_custom_key_ok() -> Result<()> {
+        let contents = "[tool.poetry]\nversion = \"0.1.0\"\n";
+        let spans = Toml::new(["tool.poetry.version"]).locate(contents.as_bytes())?;
+        assert_eq!("0.1.0", &contents[spans[0].clone()]);
+        Ok(())
+    }
+
+    #[test]
+    fn locate_ng() {
+        let inputs = vec![
+            "[package]\nname = \"bump\"\n",
+            "[dependencies]\nversion = \"1.0.0\"\n",
+            "[package\nversion = \"1.0.0\"\n",
+        ];
+        for input in inputs {
+            assert!(located(input).is_err());
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use super::{Pattern, VersionSource};
+use anyhow::Result;
+use std::ops::Range;
+
+/// A top-level scalar of a YAML document, like `version` or `appVersion` of a Helm chart.
+///
+/// Plain, single-quoted and double-quoted scalars are supported.
+#[derive(Debug, Clone)]
+pub struct Yaml {
+    pattern: Pattern,
+}
+
+impl Yaml {
+    pub fn new(key: &str) -> Self {
+        let pattern = format!(
+            r#"(?m)^{}:[ \t]*["']?(?P<version>[^"'\s#]+)"#,
+            regex::escape(key)
+        );
+        Yaml {
+            pattern: Pattern::new(&pattern).expect("escaped key makes a valid pattern"),
+        }
+    }
+}
+
+impl Default for Yaml {
+    fn default() -> Self {
+        Yaml::new("version")
+    }
+}
+
+impl VersionSource for Yaml {
+    fn locate(&self, buf: &[u8]) -> Result<Vec<Range<usize>>> {
+        self.pattern.locate(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHART: &str = r#"apiVersion: v2
+name: app
+# version: 0.0.1
+version: 0.1.0 # chart
+appVersion: "v1.16.0"
+dependencies:
+  - name: db
+    version: 12.1.0
+"#;
+
+    #[test]
+    fn locate_ok() -> Result<()> {
+        let spans = Yaml::default().locate(CHART.as_bytes())?;
+        assert_eq!(
+            vec!["0.1.0"],
+            spans.into_iter().map(|s| &CHART[s]).collect::<Vec<_>>()
+        );
+
+        let spans = Yaml::new("appVersion").locate(CHART.as_bytes())?;
+        assert_eq!(
+            vec!["v1.16.0"],
+            spans.into_iter().map(|s| &CHART[s]).collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn locate_ng() {
+        assert!(Yaml::new("kubeVersion").locate(CHART.as_bytes()).is_err());
+    }
+}
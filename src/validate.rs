@@ -0,0 +1,245 @@
+use crate::version::{prefix_len, LINE_BREAKS};
+use serde::Serialize;
+use std::fmt;
+
+/// Why a string is not a semantic version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Kind {
+    /// A part of the version core is absent, like the patch of `1.2`
+    MissingComponent,
+    /// A number other than zero starts with zero, like `01`
+    LeadingZero,
+    /// An identifier of the pre-release or build metadata is empty, like in `1.0.0-rc..1`
+    EmptyIdentifier,
+    /// A character that is not allowed where it is
+    InvalidCharacter,
+    /// A part of the version core does not fit in 64 bits
+    NumberTooLarge,
+}
+
+/// The first problem found in a version, with the byte offset it was found at
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub kind: Kind,
+    pub offset: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+const CORE: [&str; 3] = ["major", "minor", "patch"];
+
+/// Checks `s` against the Semantic Versioning 2.0.0 grammar, reporting the first problem.
+///
+/// Anything before the first digit is taken as a prefix like `v`, as when parsing a
+/// [`Version`](crate::version::Version), but trailing text is reported rather than kept as a
+/// suffix.
+pub fn validate(s: &str) -> Result<(), Diagnostic> {
+    let pos = prefix_len(s);
+    if let Some(offset) = s[..pos].find(LINE_BREAKS) {
+        return Err(Diagnostic {
+            kind: Kind::InvalidCharacter,
+            offset,
+            message: String::from("Unexpected line break in prefix"),
+        });
+    }
+    let mut cursor = Cursor { s, pos };
+
+    for (i, name) in CORE.into_iter().enumerate() {
+        if i > 0 {
+            match cursor.peek() {
+                Some('.') => cursor.pos += 1,
+                None => {
+                    return Err(
+                        cursor.error(Kind::MissingComponent, format!("Missing {name} version"))
+                    )
+                }
+                Some(c) => return Err(cursor.unexpected(c, &format!("{} version", CORE[i - 1]))),
+            }
+        }
+        cursor.number(name)?;
+    }
+
+    if cursor.peek() == Some('-') {
+        cursor.pos += 1;
+        cursor.identifiers("pre-release", true)?;
+    }
+    if cursor.peek() == Some('+') {
+        cursor.pos += 1;
+        cursor.identifiers("build metadata", false)?;
+    }
+    match cursor.peek() {
+        None => Ok(()),
+        Some(c) => Err(cursor.unexpected(c, "version")),
+    }
+}
+
+struct Cursor<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl Cursor<'_> {
+    fn peek(&self) -> Option<char> {
+        self.s[self.pos..].chars().next()
+    }
+
+    /// Advances over the characters matching `f`, returning them.
+    fn take(&mut self, f: impl Fn(char) -> bool) -> &str {
+        let start = self.pos;
+        while self.peek().is_some_and(&f) {
+            self.pos += 1;
+        }
+        &self.s[start..self.pos]
+    }
+
+    fn error(&self, kind: Kind, message: String) -> Diagnostic {
+        Diagnostic {
+            kind,
+            offset: self.pos,
+            message,
+        }
+    }
+
+    fn unexpected(&self, c: char, part: &str) -> Diagnostic {
+        self.error(
+            Kind::InvalidCharacter,
+            format!("Unexpected character '{c}' after {part}"),
+        )
+    }
+
+    fn number(&mut self, name: &str) -> Result<(), Diagnostic> {
+        let start = self.pos;
+        let digits = self.take(|c| c.is_ascii_digit());
+        if digits.is_empty() {
+            return Err(match self.peek() {
+                None | Some('.' | '-' | '+') => {
+                    self.error(Kind::MissingComponent, format!("Missing {name} version"))
+                }
+                Some(c) => self.error(
+                    Kind::InvalidCharacter,
+                    format!("Unexpected character '{c}' in {name} version"),
+                ),
+            });
+        }
+        if digits.len() > 1 && digits.starts_with('0') {
+            return Err(Diagnostic {
+                kind: Kind::LeadingZero,
+                offset: start,
+                message: format!("Leading zero in {name} version"),
+            });
+        }
+        if digits.parse::<u64>().is_err() {
+            return Err(Diagnostic {
+                kind: Kind::NumberTooLarge,
+                offset: start,
+                message: format!("Too large {name} version, the maximum is {}", u64::MAX),
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks dot separated identifiers; numeric ones can't have leading zeros if `numeric` is set.
+    fn identifiers(&mut self, part: &str, numeric: bool) -> Result<(), Diagnostic> {
+        loop {
+            let start = self.pos;
+            let id = self.take(|c| c.is_ascii_alphanumeric() || c == '-');
+            if id.is_empty() {
+                return Err(match self.peek() {
+                    None | Some('.' | '+') => {
+                        self.error(Kind::EmptyIdentifier, format!("Empty {part} identifier"))
+                    }
+                    Some(c) => self.error(
+                        Kind::InvalidCharacter,
+                        format!("Unexpected character '{c}' in {part}"),
+                    ),
+                });
+            }
+            if numeric
+                && id.len() > 1
+                && id.starts_with('0')
+                && id.bytes().all(|b| b.is_ascii_digit())
+            {
+                return Err(Diagnostic {
+                    kind: Kind::LeadingZero,
+                    offset: start,
+                    message: format!("Leading zero in numeric {part} identifier"),
+                });
+            }
+            if self.peek() != Some('.') {
+                return Ok(());
+            }
+            self.pos += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_ok() {
+        let inputs = vec![
+            "0.0.0",
+            "v1.2.3",
+            "release-10.20.30",
+            "1.0.0-rc.1",
+            "1.0.0-0A.is.legal",
+            "1.0.0-x-y-z.--",
+            "1.0.0+build.001",
+            "1.0.0-alpha+001",
+            "18446744073709551615.0.0",
+        ];
+        for input in inputs {
+            assert_eq!(Ok(()), validate(input), "{input}");
+        }
+    }
+
+    #[test]
+    fn validate_ng() {
+        let inputs = vec![
+            ("", Kind::MissingComponent, 0),
+            ("v", Kind::MissingComponent, 1),
+            ("1.2", Kind::MissingComponent, 3),
+            ("1..3", Kind::MissingComponent, 2),
+            ("1.2.", Kind::MissingComponent, 4),
+            ("v01.2.3", Kind::LeadingZero, 1),
+            ("1.2.03", Kind::LeadingZero, 4),
+            ("1.0.0-rc.01", Kind::LeadingZero, 9),
+            ("1.0.0-rc..1", Kind::EmptyIdentifier, 9),
+            ("1.0.0-", Kind::EmptyIdentifier, 6),
+            ("1.0.0+", Kind::EmptyIdentifier, 6),
+            ("1.0.0-rc.1+", Kind::EmptyIdentifier, 11),
+            ("1.2.3.4", Kind::InvalidCharacter, 5),
+            ("1.2-3", Kind::InvalidCharacter, 3),
+            ("1.x.3", Kind::InvalidCharacter, 2),
+            ("1.0.0-rc_1", Kind::InvalidCharacter, 8),
+            ("1.0.0 ", Kind::InvalidCharacter, 5),
+            ("1.0.0+build-ü", Kind::InvalidCharacter, 12),
+            ("app2-1.0.0", Kind::InvalidCharacter, 4),
+            ("app\n1.0.0", Kind::InvalidCharacter, 3),
+            ("18446744073709551616.0.0", Kind::NumberTooLarge, 0),
+        ];
+        for (input, kind, offset) in inputs {
+            let actual = validate(input).unwrap_err();
+            assert_eq!((kind, offset), (actual.kind, actual.offset), "{input}");
+        }
+    }
+
+    #[test]
+    fn diagnostic_message_ok() {
+        let actual = validate("v1.02.3").unwrap_err();
+        assert_eq!(
+            "Leading zero in minor version at byte 3",
+            actual.to_string()
+        );
+    }
+}
@@ -1,54 +1,222 @@
-use crate::fs::{read_file, read_from_stdin};
+use crate::error::Error;
+use crate::file::VersionFile;
+use crate::fs::read_from_stdin;
 use anyhow::Result;
-use anyhow::{anyhow, bail, Error};
 
 use log::*;
-use once_cell::sync::Lazy;
-use regex::Regex;
 use semver::{BuildMetadata, Prerelease, Version as SemVer};
+use serde::Serialize;
+use std::cmp::Ordering;
 use std::fmt;
 use std::io::IsTerminal;
 use std::str::FromStr;
 
-static PREFIX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"^(?P<prefix>.*?)(?P<version>[0-9]+?.[0-9]+?.[0-9]+?(?:.*)$)").unwrap()
-});
+/// Pre-release identifier used when none is given
+pub const DEFAULT_PRE_ID: &str = "rc";
 
+/// Part of the version to increment
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
+pub enum Level {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Level::Patch => "patch",
+            Level::Minor => "minor",
+            Level::Major => "major",
+        };
+        f.write_str(s)
+    }
+}
+
+/// How bumping treats a version that already is a pre-release
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum PrePolicy {
+    /// Always increment, `1.1.0-rc.1` becomes `1.2.0` for minor
+    #[default]
+    Increment,
+    /// Release a pre-release of the bumped version as npm does, `1.1.0-rc.1` becomes `1.1.0` for
+    /// minor, while `1.1.1-rc.1` still becomes `1.2.0`
+    Finalize,
+}
+
+/// One of the parts a version is made of
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Part {
+    Prefix,
+    Major,
+    Minor,
+    Patch,
+    Pre,
+    Build,
+    Suffix,
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Part::Prefix => "prefix",
+            Part::Major => "major",
+            Part::Minor => "minor",
+            Part::Patch => "patch",
+            Part::Pre => "pre",
+            Part::Build => "build",
+            Part::Suffix => "suffix",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for Part {
+    type Err = Error;
+
+    /// Parses the lowercase name of a part, as [`Part`]'s `Display` prints it.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "prefix" => Ok(Part::Prefix),
+            "major" => Ok(Part::Major),
+            "minor" => Ok(Part::Minor),
+            "patch" => Ok(Part::Patch),
+            "pre" => Ok(Part::Pre),
+            "build" => Ok(Part::Build),
+            "suffix" => Ok(Part::Suffix),
+            _ => Err(Error::Parse {
+                input: s.to_string(),
+                reason: String::from("Unknown part"),
+            }),
+        }
+    }
+}
+
+/// A semantic version, along with the text around it like `v` in `v1.2.3` or `.tar.gz` in
+/// `app-1.2.3.tar.gz`, which is kept as is.
 #[derive(PartialEq, Debug, Clone)]
 pub struct Version {
     prefix: String,
     ver: SemVer,
+    suffix: String,
 }
 
 impl FromStr for Version {
     type Err = Error;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let caps = PREFIX
-            .captures(s)
-            .ok_or_else(|| anyhow!("Can't find semver format. value: {}", s))?;
-
-        let cap_pre = caps.name("prefix");
-        let cap_ver = caps.name("version");
 
-        let (prefix, version) = match (cap_pre, cap_ver) {
-            (Some(p), Some(v)) => (p.as_str(), v.as_str()),
-            (None, Some(v)) => ("", v.as_str()),
-            _ => bail!("Can't find semver format. value: {}", s),
+    /// Parses the semantic version starting at the first digit of `s`, taking the text before it
+    /// as the prefix and the text after it as the suffix.
+    ///
+    /// A suffix that could also be read as a pre-release, like `-linux-amd64`, is read as one,
+    /// and one that continues the version, like `.4` in `1.2.3.4`, is an error; declare them with
+    /// [`Version::parse_with_suffixes`] instead. Neither the prefix nor the suffix can span lines.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: String| Error::Parse {
+            input: s.to_string(),
+            reason,
         };
+        let b = s.as_bytes();
+        let start = prefix_len(s);
+        let mut end =
+            core_end(b, start).ok_or_else(|| invalid(String::from("Can't find semver format")))?;
+        if b.get(end) == Some(&b'-') {
+            end = identifiers_end(b, end, true);
+        }
+        if b.get(end) == Some(&b'+') {
+            end = identifiers_end(b, end, false);
+        }
+        let (prefix, version, suffix) = (&s[..start], &s[start..end], &s[end..]);
 
         debug!("prefix: {prefix}");
         debug!("version: {version}");
+        debug!("suffix: {suffix}");
+
+        if prefix.contains(LINE_BREAKS) || suffix.contains(LINE_BREAKS) {
+            return Err(invalid(String::from(
+                "Unexpected line break, expected a single version",
+            )));
+        }
+        if continues_version(suffix) {
+            return Err(invalid(format!(
+                "Unexpected '{suffix}' after the version, declare it as a known suffix if it is one"
+            )));
+        }
 
         Ok(Version {
             prefix: prefix.to_string(),
-            ver: SemVer::parse(version)?,
+            ver: SemVer::parse(version).map_err(|e| invalid(e.to_string()))?,
+            suffix: suffix.to_string(),
         })
     }
 }
 
+pub(crate) const LINE_BREAKS: [char; 2] = ['\n', '\r'];
+
+/// Returns the length of the prefix of `s`, which is everything before the first digit.
+pub(crate) fn prefix_len(s: &str) -> usize {
+    s.find(|c: char| c.is_ascii_digit()).unwrap_or(s.len())
+}
+
+/// Whether `suffix` reads as a malformed rest of the version rather than text following it, like
+/// `.4` in `1.2.3.4`, `.01` in `1.0.0-rc.01`, the empty pre-release of `1.0.0-` or the build
+/// metadata of `1.0.0@20190722` with a mistyped `+`.
+fn continues_version(suffix: &str) -> bool {
+    let mut chars = suffix.chars();
+    match (chars.next(), chars.next()) {
+        (None, _) => false,
+        (Some(c), _) if c.is_alphanumeric() || c == '-' || c == '+' => true,
+        (Some('.'), next) => !next.is_some_and(|c| c.is_alphabetic()),
+        (Some(_), next) => next.is_some_and(|c| c.is_ascii_digit()),
+    }
+}
+
+/// Returns the end of the `major.minor.patch` starting at `i`, if there is one.
+fn core_end(b: &[u8], i: usize) -> Option<usize> {
+    let number = |i: usize| {
+        let len = b[i..].iter().take_while(|c| c.is_ascii_digit()).count();
+        match len {
+            0 => None,
+            1 => Some(i + 1),
+            _ if b[i] == b'0' => None,
+            _ => Some(i + len),
+        }
+    };
+    let mut end = number(i)?;
+    for _ in 0..2 {
+        if b.get(end) != Some(&b'.') {
+            return None;
+        }
+        end = number(end + 1)?;
+    }
+    Some(end)
+}
+
+/// Returns the end of the longest run of valid identifiers following the `-` or `+` at `i`, or
+/// `i` itself when there is none. Numeric identifiers can't have leading zeros if `numeric` is set.
+fn identifiers_end(b: &[u8], i: usize, numeric: bool) -> usize {
+    let mut end = i;
+    let mut pos = i + 1;
+    loop {
+        let len = b[pos..]
+            .iter()
+            .take_while(|c| c.is_ascii_alphanumeric() || **c == b'-')
+            .count();
+        let id = &b[pos..pos + len];
+        if len == 0 || numeric && len > 1 && id[0] == b'0' && id.iter().all(u8::is_ascii_digit) {
+            return end;
+        }
+        end = pos + len;
+        if b.get(end) != Some(&b'.') {
+            return end;
+        }
+        pos = end + 1;
+    }
+}
+
 impl fmt::Display for Version {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}{}", self.prefix, self.ver)
+        write!(f, "{}{}{}", self.prefix, self.ver, self.suffix)
     }
 }
 
@@ -67,55 +235,345 @@ impl TryFrom<(Option<String>, Option<String>)> for Version {
             }
             (None, Some(ref ver)) => Version::from_str(ver)?,
             (Some(_), Some(_)) => {
-                bail!("Invalid argument, specify either ver args or file option")
+                let reason = "specify either ver args or file option";
+                return Err(Error::ConflictingArguments(reason.to_string()));
             }
-            (Some(f), _) => {
-                let buf = String::from_utf8(read_file(f)?)?;
-                Version::from_str(buf.trim_end())?
+            (Some(f), _) => VersionFile::open(f)?.version().clone(),
+            _ => {
+                let reason = "specify a version, a file or pipe one to stdin";
+                return Err(Error::ConflictingArguments(reason.to_string()));
             }
-            _ => bail!("Invalid argument"),
         };
         Ok(v)
     }
 }
 
 impl Version {
-    pub fn bump_patch(&self) -> Version {
+    /// Parses like [`FromStr`], except that the longest of `known` that ends `s` is taken as the
+    /// suffix, so that `1.2.3-linux-amd64` is not read as a pre-release.
+    pub fn parse_with_suffixes<S: AsRef<str>>(s: &str, known: &[S]) -> Result<Version, Error> {
+        let known = known
+            .iter()
+            .map(AsRef::as_ref)
+            .filter(|k| !k.is_empty() && s.ends_with(k))
+            .max_by_key(|k| k.len());
+        match known {
+            Some(known) => {
+                let mut v = Version::from_str(&s[..s.len() - known.len()])?;
+                v.suffix.push_str(known);
+                Ok(v)
+            }
+            None => Version::from_str(s),
+        }
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    pub fn suffix(&self) -> &str {
+        &self.suffix
+    }
+
+    pub fn semver(&self) -> &SemVer {
+        &self.ver
+    }
+
+    /// Whether the pre-release is only words joined by dashes, like `linux-amd64`, or goes on with
+    /// words after its number, like `rc.1.tar.gz`, which is more likely a suffix that wasn't
+    /// declared as one.
+    pub fn pre_looks_like_suffix(&self) -> bool {
+        let pre = self.ver.pre.as_str();
+        let numeric = |word: &str| word.bytes().all(|b| b.is_ascii_digit());
+        let dashed = pre.contains('-')
+            && pre
+                .split(['.', '-'])
+                .all(|word| !word.is_empty() && !numeric(word));
+        let after_number = pre
+            .split('.')
+            .skip_while(|word| !numeric(word))
+            .any(|word| !numeric(word));
+        dashed || after_number
+    }
+
+    /// Compares by semver precedence, which ignores the prefix and build metadata.
+    pub fn cmp_precedence(&self, other: &Version) -> Ordering {
+        self.ver.cmp_precedence(&other.ver)
+    }
+
+    /// Returns the most significant part that differs from `before`, if any.
+    pub fn changed_part(&self, before: &Version) -> Option<Part> {
+        let (a, b) = (&self.ver, &before.ver);
+        if a.major != b.major {
+            Some(Part::Major)
+        } else if a.minor != b.minor {
+            Some(Part::Minor)
+        } else if a.patch != b.patch {
+            Some(Part::Patch)
+        } else if a.pre != b.pre {
+            Some(Part::Pre)
+        } else if a.build != b.build {
+            Some(Part::Build)
+        } else if self.prefix != before.prefix {
+            Some(Part::Prefix)
+        } else if self.suffix != before.suffix {
+            Some(Part::Suffix)
+        } else {
+            None
+        }
+    }
+
+    /// Returns one part as text, empty for a missing pre-release, build metadata or suffix.
+    pub fn get(&self, part: Part) -> String {
+        match part {
+            Part::Prefix => self.prefix.clone(),
+            Part::Major => self.ver.major.to_string(),
+            Part::Minor => self.ver.minor.to_string(),
+            Part::Patch => self.ver.patch.to_string(),
+            Part::Pre => self.ver.pre.to_string(),
+            Part::Build => self.ver.build.to_string(),
+            Part::Suffix => self.suffix.clone(),
+        }
+    }
+
+    /// Fills a template like `{prefix}{major}.{minor}` with the parts of the version.
+    ///
+    /// `{version}` is the whole version, and `{{` and `}}` are literal braces.
+    pub fn format(&self, template: &str) -> Result<String, Error> {
+        let mut out = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(i) = rest.find(['{', '}']) {
+            out.push_str(&rest[..i]);
+            let (brace, tail) = rest[i..].split_at(1);
+            if let Some(tail) = tail.strip_prefix(brace) {
+                out.push_str(brace);
+                rest = tail;
+                continue;
+            }
+            let name = match (brace, tail.find('}')) {
+                ("{", Some(end)) => &tail[..end],
+                _ => {
+                    let at = template.len() - rest.len() + i;
+                    return Err(Error::InvalidTemplate(format!(
+                        "unmatched '{brace}' at {at}"
+                    )));
+                }
+            };
+            match name {
+                "version" => out.push_str(&self.to_string()),
+                name => match Part::from_str(name) {
+                    Ok(part) => out.push_str(&self.get(part)),
+                    Err(_) => {
+                        return Err(Error::InvalidTemplate(format!("unknown field {{{name}}}")))
+                    }
+                },
+            }
+            rest = &tail[name.len() + 1..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+
+    /// Fails unless the prefix is empty or one of `allowed`, which allows any prefix when empty.
+    pub fn check_prefix<S: AsRef<str>>(&self, allowed: &[S]) -> Result<(), Error> {
+        if self.prefix.is_empty()
+            || allowed.is_empty()
+            || allowed.iter().any(|p| p.as_ref() == self.prefix)
+        {
+            return Ok(());
+        }
+        let allowed = allowed.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+        Err(Error::Parse {
+            input: self.to_string(),
+            reason: format!(
+                "Unexpected prefix '{}', allowed: {}",
+                self.prefix,
+                allowed.join(", ")
+            ),
+        })
+    }
+
+    pub fn with_prefix(&self, prefix: impl Into<String>) -> Version {
+        let mut v = self.clone();
+        v.prefix = prefix.into();
+        v
+    }
+
+    pub fn with_suffix(&self, suffix: impl Into<String>) -> Version {
+        let mut v = self.clone();
+        v.suffix = suffix.into();
+        v
+    }
+
+    pub fn bump(&self, level: Level) -> Result<Version, Error> {
+        match level {
+            Level::Patch => self.bump_patch(),
+            Level::Minor => self.bump_minor(),
+            Level::Major => self.bump_major(),
+        }
+    }
+
+    pub fn bump_with(&self, level: Level, policy: PrePolicy) -> Result<Version, Error> {
+        let ver = &self.ver;
+        let target = match level {
+            Level::Patch => true,
+            Level::Minor => ver.patch == 0,
+            Level::Major => ver.minor == 0 && ver.patch == 0,
+        };
+        if policy == PrePolicy::Finalize && !ver.pre.is_empty() && target {
+            let mut v = self.release();
+            v.ver.build = BuildMetadata::EMPTY;
+            return Ok(v);
+        }
+        self.bump(level)
+    }
+
+    /// Replaces the version with `new`, refusing to go backwards by semver precedence unless
+    /// `allow_downgrade` is set.
+    ///
+    /// The current prefix and suffix are kept when `new` has none.
+    pub fn set(&self, new: &Version, allow_downgrade: bool) -> Result<Version, Error> {
+        if !allow_downgrade && new.cmp_precedence(self) == Ordering::Less {
+            return Err(Error::Downgrade {
+                current: self.to_string(),
+                new: new.to_string(),
+            });
+        }
+        let mut v = new.clone();
+        if v.prefix.is_empty() {
+            v.prefix = self.prefix.clone();
+        }
+        if v.suffix.is_empty() {
+            v.suffix = self.suffix.clone();
+        }
+        Ok(v)
+    }
+
+    /// Removes the pre-release, keeping the build metadata.
+    pub fn release(&self) -> Version {
         let mut v = self.clone();
-        v.ver.patch += 1;
         v.ver.pre = Prerelease::EMPTY;
-        v.ver.build = BuildMetadata::EMPTY;
         v
     }
 
-    pub fn bump_minor(&self) -> Version {
+    /// Returns `n + 1`, or an overflow error for `part` when `n` is already the maximum.
+    fn increment(&self, n: u64, part: Part) -> Result<u64, Error> {
+        n.checked_add(1).ok_or_else(|| self.overflow(part))
+    }
+
+    fn overflow(&self, part: Part) -> Error {
+        Error::Overflow {
+            part,
+            version: self.to_string(),
+        }
+    }
+
+    pub fn bump_patch(&self) -> Result<Version, Error> {
         let mut v = self.clone();
-        v.ver.minor += 1;
+        v.ver.patch = self.increment(self.ver.patch, Part::Patch)?;
+        v.ver.pre = Prerelease::EMPTY;
+        v.ver.build = BuildMetadata::EMPTY;
+        Ok(v)
+    }
+
+    pub fn bump_minor(&self) -> Result<Version, Error> {
+        let mut v = self.clone();
+        v.ver.minor = self.increment(self.ver.minor, Part::Minor)?;
         v.ver.patch = 0;
         v.ver.pre = Prerelease::EMPTY;
         v.ver.build = BuildMetadata::EMPTY;
-        v
+        Ok(v)
     }
 
-    pub fn bump_major(&self) -> Version {
+    pub fn bump_major(&self) -> Result<Version, Error> {
         let mut v = self.clone();
-        v.ver.major += 1;
+        v.ver.major = self.increment(self.ver.major, Part::Major)?;
         v.ver.minor = 0;
         v.ver.patch = 0;
         v.ver.pre = Prerelease::EMPTY;
         v.ver.build = BuildMetadata::EMPTY;
-        v
+        Ok(v)
     }
 
-    pub fn update_pre_release(&self, pre: impl Into<String>) -> Result<Version> {
+    pub fn update_pre_release(&self, pre: impl Into<String>) -> Result<Version, Error> {
+        let pre = pre.into();
         let mut v = self.clone();
-        v.ver.pre = Prerelease::new(pre.into().as_str())?;
+        v.ver.pre = Prerelease::new(&pre).map_err(|e| Error::InvalidPreRelease {
+            value: pre.clone(),
+            reason: e.to_string(),
+        })?;
         Ok(v)
     }
 
-    pub fn update_build(&self, build: impl Into<String>) -> Result<Version> {
+    /// Increments the pre-release counter, the last numeric identifier of the pre-release.
+    ///
+    /// `id` is the identifier the counter belongs to, empty to keep the current one:
+    ///
+    /// - `rc.3` becomes `rc.4`, and `rc` becomes `rc.0`
+    /// - switching identifiers resets the counter, `alpha.3` becomes `beta.0`
+    /// - identifiers following the counter are dropped, `beta.2.hotfix` becomes `beta.3`
+    /// - without a pre-release, the counter starts at `id.0`
+    pub fn next_pre_release(&self, id: impl Into<String>) -> Result<Version, Error> {
+        let id = id.into();
+        let last = id.rsplit('.').next().unwrap_or_default();
+        if !id.is_empty() && last.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(Error::InvalidPreRelease {
+                value: id,
+                reason: String::from("the identifier must not end with a number"),
+            });
+        }
+
+        let parts = self.ver.pre.as_str().split('.').collect::<Vec<_>>();
+        let counter = parts
+            .iter()
+            .rposition(|p| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit()));
+        let label = match counter {
+            Some(i) => parts[..i].join("."),
+            None => self.ver.pre.to_string(),
+        };
+
+        let pre = match counter {
+            Some(i) if id.is_empty() || id == label => {
+                let n = parts[i]
+                    .parse::<u64>()
+                    .map_err(|_| self.overflow(Part::Pre))
+                    .and_then(|n| self.increment(n, Part::Pre))?;
+                let mut parts = parts[..i].to_vec();
+                let n = n.to_string();
+                parts.push(&n);
+                parts.join(".")
+            }
+            _ if id.is_empty() && label.is_empty() => String::from("0"),
+            _ if id.is_empty() => format!("{label}.0"),
+            _ => format!("{id}.0"),
+        };
+        self.update_pre_release(pre)
+    }
+
+    /// Bumps `level` and starts a pre-release of it, like `1.2.3` to `1.3.0-rc.0` for minor.
+    pub fn bump_pre(&self, level: Level, id: impl Into<String>) -> Result<Version, Error> {
+        self.bump(level)?.next_pre_release(id)
+    }
+
+    /// Increments the pre-release counter, or starts a pre-release of the next patch when there is
+    /// no pre-release yet.
+    ///
+    /// Without `id`, the current identifier is kept, or [`DEFAULT_PRE_ID`] is used to start one.
+    pub fn bump_pre_release(&self, id: Option<&str>) -> Result<Version, Error> {
+        if self.ver.pre.is_empty() {
+            self.bump_pre(Level::Patch, id.unwrap_or(DEFAULT_PRE_ID))
+        } else {
+            self.next_pre_release(id.unwrap_or_default())
+        }
+    }
+
+    pub fn update_build(&self, build: impl Into<String>) -> Result<Version, Error> {
+        let build = build.into();
         let mut v = self.clone();
-        v.ver.build = BuildMetadata::new(build.into().as_str())?;
+        v.ver.build = BuildMetadata::new(&build).map_err(|e| Error::InvalidBuild {
+            value: build.clone(),
+            reason: e.to_string(),
+        })?;
         Ok(v)
     }
 }
@@ -123,6 +581,7 @@ impl Version {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn from_str_ok() {
@@ -130,9 +589,96 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn from_str_span_ok() -> Result<()> {
+        let inputs = vec![
+            ("app-1.2.3.tar.gz", "app-", "1.2.3", ".tar.gz"),
+            (
+                "v1.0.0-rc.1+build.5 (beta)",
+                "v",
+                "1.0.0-rc.1+build.5",
+                " (beta)",
+            ),
+            ("chart-1.2.3-linux-amd64", "chart-", "1.2.3-linux-amd64", ""),
+            ("version = \"1.2.3\"", "version = \"", "1.2.3", "\""),
+            ("1.2.3_linux", "", "1.2.3", "_linux"),
+        ];
+        for (input, prefix, ver, suffix) in inputs {
+            let version = Version::from_str(input)?;
+            assert_eq!(prefix, version.prefix(), "{input}");
+            assert_eq!(ver, version.semver().to_string(), "{input}");
+            assert_eq!(suffix, version.suffix(), "{input}");
+            assert_eq!(input, version.to_string());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn from_str_span_ng() {
+        let inputs = vec![
+            "1.0.0-rc.01",
+            "1.0.0-rc..1",
+            "1.0.0-",
+            "1.0.0+",
+            "1.0.0-+x",
+            "1.0.0-rc_1",
+            "1.2.3.4",
+            "1.2.3.",
+            "1.2.3a",
+            "0.0.0@20190722",
+            "app2-1.0.0",
+            "v01.2.3 and 4.5.6",
+            "1.2.3\n1.2.4",
+            "version\n1.2.3",
+            "requires = [\"setuptools>=61.0.0\"]\nversion = \"1.2.3\"",
+        ];
+        for input in inputs {
+            assert!(Version::from_str(input).is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn pre_looks_like_suffix_ok() -> Result<()> {
+        let inputs = vec![
+            ("1.2.3-linux-amd64", true),
+            ("1.2.3-linux-amd64.gnu", true),
+            ("1.2.3", false),
+            ("1.2.3-rc", false),
+            ("1.2.3-rc.1", false),
+            ("1.2.3-rc.1.tar.gz", true),
+            ("1.2.3-beta.2.linux", true),
+            ("1.2.3-alpha.beta.1", false),
+            ("1.2.3-1.2", false),
+            ("1.2.3-alpha-1", false),
+            ("1.2.3-x-y-z.--", false),
+        ];
+        for (input, expect) in inputs {
+            let version = Version::from_str(input)?;
+            assert_eq!(expect, version.pre_looks_like_suffix(), "{input}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn parse_with_suffixes_ok() -> Result<()> {
+        let known = ["-linux-amd64", ".tar.gz", "-amd64"];
+        let version = Version::parse_with_suffixes("chart-1.2.3-linux-amd64", &known)?;
+        assert_eq!("1.2.3", version.semver().to_string());
+        assert_eq!("-linux-amd64", version.suffix());
+        assert_eq!("chart-1.2.4-linux-amd64", version.bump_patch()?.to_string());
+
+        let version = Version::parse_with_suffixes("1.2.3-rc.1.tar.gz", &known)?;
+        assert_eq!("1.2.3-rc.1", version.semver().to_string());
+        assert_eq!("1.2.3.tar.gz", version.release().to_string());
+
+        let version = Version::parse_with_suffixes("1.2.3-rc.1", &known)?;
+        assert_eq!("rc.1", version.get(Part::Pre));
+        Ok(())
+    }
+
     #[test]
     fn from_str_ng() {
-        let inputs = vec!["x.x.x", "vx.x.x", "x", "x.x"];
+        let inputs = vec!["x.x.x", "vx.x.x", "x", "x.x", "01.2.3", "1.02.3", "1.2"];
         for input in inputs {
             let result = Version::from_str(input);
             assert!(result.is_err());
@@ -141,13 +687,319 @@ mod tests {
         }
     }
 
+    #[test]
+    fn next_pre_release_ok() -> Result<()> {
+        let inputs = vec![
+            ("1.0.0-rc.3", "rc", "1.0.0-rc.4"),
+            ("1.0.0-rc.3", "", "1.0.0-rc.4"),
+            ("1.0.0-rc.9+build", "rc", "1.0.0-rc.10+build"),
+            ("1.0.0-rc", "rc", "1.0.0-rc.0"),
+            ("1.0.0-rc", "", "1.0.0-rc.0"),
+            ("1.0.0-alpha.3", "beta", "1.0.0-beta.0"),
+            ("1.0.0-beta.2.hotfix", "beta", "1.0.0-beta.3"),
+            ("1.0.0-beta.2.hotfix", "beta.hotfix", "1.0.0-beta.hotfix.0"),
+            ("1.0.0-x.7.z.92", "x.7.z", "1.0.0-x.7.z.93"),
+            ("1.0.0-7", "", "1.0.0-8"),
+            ("1.0.0", "rc", "1.0.0-rc.0"),
+            ("1.0.0", "", "1.0.0-0"),
+            ("v1.0.0-rc.1", "rc", "v1.0.0-rc.2"),
+        ];
+        for (version, id, expect) in inputs {
+            let actual = Version::from_str(version)?.next_pre_release(id)?;
+            assert_eq!(expect, actual.to_string(), "{version} {id}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn next_pre_release_ng() -> Result<()> {
+        let version = Version::from_str("1.0.0-rc.1")?;
+        assert!(version.next_pre_release("rc.1").is_err());
+        assert!(version.next_pre_release("r c").is_err());
+
+        let version = Version::from_str("1.0.0-rc.18446744073709551615")?;
+        assert!(version.next_pre_release("rc").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn bump_pre_ok() -> Result<()> {
+        let inputs = vec![
+            ("1.2.3", Level::Patch, "rc", "1.2.4-rc.0"),
+            ("1.2.3", Level::Minor, "rc", "1.3.0-rc.0"),
+            ("1.2.3", Level::Major, "beta", "2.0.0-beta.0"),
+            ("1.2.3", Level::Major, "", "2.0.0-0"),
+            ("v1.2.3-rc.1+build", Level::Minor, "rc", "v1.3.0-rc.0"),
+        ];
+        for (version, level, id, expect) in inputs {
+            let actual = Version::from_str(version)?.bump_pre(level, id)?;
+            assert_eq!(expect, actual.to_string(), "{version} {level} {id}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn bump_pre_release_ok() -> Result<()> {
+        let inputs = vec![
+            ("1.2.3", None, "1.2.4-rc.0"),
+            ("1.2.3", Some("alpha"), "1.2.4-alpha.0"),
+            ("1.3.0-rc.0", None, "1.3.0-rc.1"),
+            ("1.3.0-alpha.4", Some("beta"), "1.3.0-beta.0"),
+            ("1.3.0-alpha.4", Some("alpha"), "1.3.0-alpha.5"),
+        ];
+        for (version, id, expect) in inputs {
+            let actual = Version::from_str(version)?.bump_pre_release(id)?;
+            assert_eq!(expect, actual.to_string(), "{version} {id:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn bump_with_finalize_ok() -> Result<()> {
+        let inputs = vec![
+            ("1.1.0-rc.1", Level::Minor, "1.1.0"),
+            ("1.1.1-rc.1", Level::Minor, "1.2.0"),
+            ("1.1.1-rc.1+build", Level::Patch, "1.1.1"),
+            ("2.0.0-rc.1", Level::Major, "2.0.0"),
+            ("2.1.0-rc.1", Level::Major, "3.0.0"),
+            ("2.0.0-rc.1", Level::Minor, "2.0.0"),
+            ("1.1.0", Level::Minor, "1.2.0"),
+        ];
+        for (version, level, expect) in inputs {
+            let actual = Version::from_str(version)?.bump_with(level, PrePolicy::Finalize)?;
+            assert_eq!(expect, actual.to_string(), "{version} {level}");
+        }
+
+        let actual =
+            Version::from_str("1.1.0-rc.1")?.bump_with(Level::Minor, PrePolicy::Increment)?;
+        assert_eq!("1.2.0", actual.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn set_ok() -> Result<()> {
+        let current = Version::from_str("v2.1.0")?;
+        let inputs = vec![
+            ("3.0.0", false, "v3.0.0"),
+            ("release-3.0.0", false, "release-3.0.0"),
+            ("2.1.0+build", false, "v2.1.0+build"),
+            ("1.0.0", true, "v1.0.0"),
+        ];
+        for (new, allow_downgrade, expect) in inputs {
+            let actual = current.set(&Version::from_str(new)?, allow_downgrade)?;
+            assert_eq!(expect, actual.to_string(), "{new}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn set_ng() -> Result<()> {
+        let current = Version::from_str("2.1.0")?;
+        for new in ["2.0.9", "2.1.0-rc.1"] {
+            let msg = current
+                .set(&Version::from_str(new)?, false)
+                .expect_err("")
+                .to_string();
+            assert!(msg.contains("goes backwards"));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn release_ok() -> Result<()> {
+        let actual = Version::from_str("v2.0.0-rc.4+build.1")?.release();
+        assert_eq!("v2.0.0+build.1", actual.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn changed_part_ok() -> Result<()> {
+        let before = Version::from_str("v1.2.3-rc.1+build")?;
+        let inputs = vec![
+            ("v2.0.0", Some(Part::Major)),
+            ("v1.3.0-rc.1+build", Some(Part::Minor)),
+            ("v1.2.4", Some(Part::Patch)),
+            ("v1.2.3+build", Some(Part::Pre)),
+            ("v1.2.3-rc.1", Some(Part::Build)),
+            ("1.2.3-rc.1+build", Some(Part::Prefix)),
+            ("v1.2.3-rc.1+build", None),
+        ];
+        for (after, expect) in inputs {
+            let after = Version::from_str(after)?;
+            assert_eq!(expect, after.changed_part(&before), "{after}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn part_from_str_ok() -> Result<()> {
+        for part in [
+            Part::Prefix,
+            Part::Major,
+            Part::Minor,
+            Part::Patch,
+            Part::Pre,
+            Part::Build,
+            Part::Suffix,
+        ] {
+            assert_eq!(part, Part::from_str(&part.to_string())?);
+        }
+        assert!(Part::from_str("Major").is_err());
+        assert!(Part::from_str("version").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn format_ok() -> Result<()> {
+        let version = Version::from_str("v1.4.2-rc.1+build.5")?;
+        let inputs = vec![
+            ("{major}.{minor}", "1.4"),
+            ("{prefix}{major}", "v1"),
+            ("{pre}", "rc.1"),
+            ("{patch}+{build}", "2+build.5"),
+            ("app:{version}", "app:v1.4.2-rc.1+build.5"),
+            ("{{major}} is {major}", "{major} is 1"),
+            ("", ""),
+        ];
+        for (template, expect) in inputs {
+            assert_eq!(expect, version.format(template)?, "{template}");
+        }
+        assert_eq!("", Version::from_str("1.0.0")?.format("{pre}")?);
+        Ok(())
+    }
+
+    #[test]
+    fn format_ng() -> Result<()> {
+        let version = Version::from_str("1.4.2")?;
+        for template in ["{major", "major}", "{}", "{Major}", "{micro}"] {
+            assert!(version.format(template).is_err(), "{template}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn error_kind_ok() -> Result<()> {
+        let version = Version::from_str("1.0.0-rc.1")?;
+        assert!(matches!(
+            Version::from_str("v1.0"),
+            Err(Error::Parse { .. })
+        ));
+        assert!(matches!(
+            version.update_pre_release("rc..1"),
+            Err(Error::InvalidPreRelease { .. })
+        ));
+        assert!(matches!(
+            version.update_build("a+b"),
+            Err(Error::InvalidBuild { .. })
+        ));
+        assert!(matches!(
+            version.set(&Version::from_str("0.9.0")?, false),
+            Err(Error::Downgrade { .. })
+        ));
+        assert!(matches!(
+            version.format("{micro}"),
+            Err(Error::InvalidTemplate(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn bump_overflow_ng() -> Result<()> {
+        let max = u64::MAX;
+        let inputs = vec![
+            (format!("1.2.{max}"), Level::Patch, Part::Patch),
+            (format!("1.{max}.0"), Level::Minor, Part::Minor),
+            (format!("v{max}.0.0"), Level::Major, Part::Major),
+        ];
+        for (version, level, part) in inputs {
+            let actual = Version::from_str(&version)?.bump(level).unwrap_err();
+            assert_eq!(
+                format!("Can't bump {part} any further. value: {version}"),
+                actual.to_string()
+            );
+        }
+        // Higher parts still bump, as the overflowing one is reset
+        let version = Version::from_str(&format!("1.{max}.{max}"))?;
+        assert_eq!("2.0.0", version.bump_major()?.to_string());
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn bump_never_panics(major: u64, minor: u64, patch: u64, level in 0..3) {
+            let version = Version::from_str(&format!("{major}.{minor}.{patch}")).unwrap();
+            let (level, n) = match level {
+                0 => (Level::Patch, patch),
+                1 => (Level::Minor, minor),
+                _ => (Level::Major, major),
+            };
+            match version.bump(level) {
+                Ok(bumped) => {
+                    prop_assert!(n < u64::MAX);
+                    prop_assert_eq!(Ordering::Greater, bumped.cmp_precedence(&version));
+                }
+                Err(err) => {
+                    prop_assert_eq!(u64::MAX, n);
+                    prop_assert!(matches!(err, Error::Overflow { .. }), "{}", err);
+                }
+            }
+        }
+
+        #[test]
+        fn bump_boundary(offset in 0u64..4, level in 0..3) {
+            let n = u64::MAX - offset;
+            let (version, level) = match level {
+                0 => (format!("0.0.{n}"), Level::Patch),
+                1 => (format!("0.{n}.0"), Level::Minor),
+                _ => (format!("{n}.0.0"), Level::Major),
+            };
+            let actual = Version::from_str(&version).unwrap().bump(level);
+            prop_assert_eq!(offset == 0, actual.is_err());
+        }
+
+        #[test]
+        fn next_pre_release_boundary(offset in 0u64..4) {
+            let n = u64::MAX - offset;
+            let version = Version::from_str(&format!("1.0.0-rc.{n}")).unwrap();
+            match version.next_pre_release("rc") {
+                Ok(next) => prop_assert_eq!(format!("1.0.0-rc.{}", n + 1), next.to_string()),
+                Err(err) => {
+                    prop_assert_eq!(0, offset);
+                    prop_assert!(matches!(err, Error::Overflow { part: Part::Pre, .. }), "{}", err);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn check_prefix_ok() -> Result<()> {
+        let allowed = ["v", "release-"];
+        for version in ["v1.0.0", "release-1.0.0", "1.0.0"] {
+            assert!(Version::from_str(version)?.check_prefix(&allowed).is_ok());
+        }
+        let actual = Version::from_str("build 1.0.0")?.check_prefix(&allowed);
+        assert_eq!(
+            "Unexpected prefix 'build ', allowed: v, release-. value: build 1.0.0",
+            actual.unwrap_err().to_string()
+        );
+        let none: [&str; 0] = [];
+        assert!(Version::from_str("anything-1.0.0")?
+            .check_prefix(&none)
+            .is_ok());
+        Ok(())
+    }
+
     #[test]
     fn try_from_ok() {
         let result = Version::try_from((None, Some(String::from("0.0.0"))));
 
         let prefix = String::from("");
         let ver = SemVer::new(0, 0, 0);
-        let _version = Version { prefix, ver };
+        let _version = Version {
+            prefix,
+            ver,
+            suffix: String::new(),
+        };
         assert!(matches!(result, Ok(_version)));
     }
 
@@ -161,5 +1013,8 @@ mod tests {
             let result = Version::try_from((file, ver));
             assert!(result.is_err());
         }
+
+        let result = Version::try_from((Some(String::from("not_found")), None));
+        assert!(matches!(result, Err(Error::Io { .. })));
     }
 }
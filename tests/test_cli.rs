@@ -1,11 +1,50 @@
 use assert_cmd::Command;
 use predicates::prelude::*;
+use std::path::Path;
+use std::process::Output;
+use tempfile::TempDir;
 
 const EXIT_CODE_OK: i32 = 0;
+const EXIT_CODE_CHANGED: i32 = 1;
 const EXIT_CODE_USAGE: i32 = 2;
+const EXIT_CODE_MISMATCH: i32 = 1;
+const EXIT_CODE_PARSE: i32 = 3;
+const EXIT_CODE_OVERFLOW: i32 = 6;
+const EXIT_CODE_IO: i32 = 7;
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
+fn git(repo: &Path, args: &[&str]) -> Result<Output> {
+    let out = std::process::Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()?;
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        return Err(format!("git {}: {stderr}", args.join(" ")).into());
+    }
+    Ok(out)
+}
+
+/// Creates a repository with one empty commit tagged with each of `tags`.
+fn repo(tags: &[&str]) -> Result<TempDir> {
+    let dir = tempfile::tempdir()?;
+    git(dir.path(), &["init", "-q"])?;
+    git(dir.path(), &["config", "user.name", "bump"])?;
+    git(dir.path(), &["config", "user.email", "bump@example.com"])?;
+    commit(dir.path(), "initial commit")?;
+    for tag in tags {
+        git(dir.path(), &["tag", tag])?;
+    }
+    Ok(dir)
+}
+
+fn commit(repo: &Path, message: &str) -> Result<()> {
+    git(repo, &["commit", "-q", "--allow-empty", "-m", message])?;
+    Ok(())
+}
+
 #[test]
 fn help_ok() -> Result<()> {
     let mut cmd = Command::cargo_bin("bump")?;
@@ -73,7 +112,7 @@ fn ng() -> Result<()> {
     cmd.args(["patch", "x.x.x"]);
     cmd.assert()
         .failure()
-        .code(EXIT_CODE_USAGE)
+        .code(EXIT_CODE_PARSE)
         .stderr(predicate::str::contains("Can't find semver format."));
     Ok(())
 }
@@ -135,3 +174,382 @@ fn hyphen_ok() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn dry_run_ok() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let path = tmp_dir.path().join("version.txt");
+    std::fs::write(&path, "0.1.0\n")?;
+
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.args(["patch", "--dry-run", "-f"]).arg(&path);
+    cmd.assert()
+        .failure()
+        .code(EXIT_CODE_CHANGED)
+        .stdout(predicate::str::contains("-0.1.0\n+0.1.1\n"));
+    assert_eq!("0.1.0\n", std::fs::read_to_string(&path)?);
+
+    Ok(())
+}
+
+#[test]
+fn config_ignored_for_stdin_ok() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let path = tmp_dir.path().join("VERSION");
+    std::fs::write(
+        tmp_dir.path().join(".bump.toml"),
+        "[[file]]\npath = \"VERSION\"\n",
+    )?;
+    std::fs::write(&path, "5.0.0\n")?;
+
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.current_dir(tmp_dir.path()).arg("patch");
+    cmd.write_stdin("1.2.3\n")
+        .assert()
+        .success()
+        .code(EXIT_CODE_OK)
+        .stdout("1.2.4\n");
+    assert_eq!("5.0.0\n", std::fs::read_to_string(&path)?);
+
+    Ok(())
+}
+
+#[test]
+fn from_git_ok() -> Result<()> {
+    let tmp_dir = repo(&["v1.4.2", "v1.10.0"])?;
+
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.current_dir(tmp_dir.path())
+        .args(["minor", "--from-git"]);
+    cmd.assert()
+        .success()
+        .code(EXIT_CODE_OK)
+        .stdout("v1.11.0\n");
+
+    Ok(())
+}
+
+#[test]
+fn tag_ok() -> Result<()> {
+    let tmp_dir = repo(&["release-1.0.0"])?;
+
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.current_dir(tmp_dir.path()).args([
+        "patch",
+        "--from-git",
+        "--tag",
+        "--tag-message",
+        "Hotfix",
+    ]);
+    cmd.assert()
+        .success()
+        .code(EXIT_CODE_OK)
+        .stdout("release-1.0.1\n");
+
+    let out = git(
+        tmp_dir.path(),
+        &["tag", "--list", "--format=%(refname:short) %(contents)"],
+    )?;
+    let tags = String::from_utf8(out.stdout)?;
+    assert!(tags.contains("release-1.0.1 Hotfix"));
+
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.current_dir(tmp_dir.path())
+        .args(["patch", "--tag", "release-1.0.0"]);
+    cmd.assert()
+        .failure()
+        .code(EXIT_CODE_USAGE)
+        .stderr(predicate::str::contains("Tag already exists"));
+
+    let path = tmp_dir.path().join("VERSION");
+    std::fs::write(&path, "1.0.1\n")?;
+    git(tmp_dir.path(), &["add", "VERSION"])?;
+    git(tmp_dir.path(), &["commit", "-q", "-m", "add version file"])?;
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.current_dir(tmp_dir.path())
+        .args(["patch", "--tag", "-w", "-f", "VERSION"]);
+    cmd.assert()
+        .failure()
+        .code(EXIT_CODE_USAGE)
+        .stderr(predicate::str::contains(
+            "--tag can't be used while rewriting files",
+        ));
+    assert_eq!("1.0.1\n", std::fs::read_to_string(&path)?);
+    let out = git(tmp_dir.path(), &["tag", "--list", "1.0.2"])?;
+    assert!(out.stdout.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn auto_ok() -> Result<()> {
+    let tmp_dir = repo(&["v1.4.2"])?;
+    commit(tmp_dir.path(), "fix: typo")?;
+
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.current_dir(tmp_dir.path()).args(["auto", "--explain"]);
+    cmd.assert()
+        .success()
+        .code(EXIT_CODE_OK)
+        .stdout("v1.4.3\n")
+        .stderr(predicate::str::starts_with("patch: "));
+
+    commit(tmp_dir.path(), "feat(cli): auto")?;
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.current_dir(tmp_dir.path()).args(["auto"]);
+    cmd.assert().success().stdout("v1.5.0\n");
+
+    commit(tmp_dir.path(), "feat!: break")?;
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.current_dir(tmp_dir.path()).args(["auto", "0.3.0"]);
+    cmd.assert().success().stdout("0.4.0\n");
+
+    Ok(())
+}
+
+#[test]
+fn auto_write_ok() -> Result<()> {
+    let tmp_dir = repo(&["v1.4.2"])?;
+    commit(tmp_dir.path(), "feat: write")?;
+
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.current_dir(tmp_dir.path()).args(["auto", "-w"]);
+    cmd.assert().failure().code(EXIT_CODE_USAGE);
+
+    let path = tmp_dir.path().join("VERSION");
+    std::fs::write(
+        tmp_dir.path().join(".bump.toml"),
+        "[[file]]\npath = \"VERSION\"\n",
+    )?;
+    std::fs::write(&path, "1.4.2\n")?;
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.current_dir(tmp_dir.path()).args(["auto", "-w"]);
+    cmd.assert().success().code(EXIT_CODE_OK).stdout("1.5.0\n");
+    assert_eq!("1.5.0\n", std::fs::read_to_string(&path)?);
+
+    Ok(())
+}
+
+#[test]
+fn changelog_ok() -> Result<()> {
+    let tmp_dir = repo(&["v1.0.0"])?;
+    commit(tmp_dir.path(), "feat(cli): add changelog")?;
+    commit(tmp_dir.path(), "fix: typo")?;
+    let changelog = tmp_dir.path().join("CHANGELOG.md");
+    std::fs::write(
+        &changelog,
+        "# Changelog\n\n## [Unreleased]\n\n## [1.0.0] - 2026-01-01\n",
+    )?;
+
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.current_dir(tmp_dir.path())
+        .args(["auto", "--changelog", "CHANGELOG.md"]);
+    cmd.assert().success().code(EXIT_CODE_OK).stdout("v1.1.0\n");
+
+    let actual = std::fs::read_to_string(&changelog)?;
+    assert!(actual.starts_with("# Changelog\n\n## [Unreleased]\n\n## [1.1.0] - "));
+    assert!(actual.contains("### Features\n\n- **cli:** add changelog ("));
+    assert!(actual.contains("### Bug Fixes\n\n- typo ("));
+    assert!(actual.ends_with("\n\n## [1.0.0] - 2026-01-01\n"));
+
+    Ok(())
+}
+
+#[test]
+fn compare_ok() -> Result<()> {
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.args(["compare", "--expect", "gt", "v1.10.0", "v1.9.0"]);
+    cmd.assert().success().code(EXIT_CODE_OK).stdout(">\n");
+
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.args(["compare", "--expect", "gt", "1.0.0+b", "1.0.0+a"]);
+    cmd.assert()
+        .failure()
+        .code(EXIT_CODE_MISMATCH)
+        .stdout("=\n");
+    Ok(())
+}
+
+#[test]
+fn sort_ok() -> Result<()> {
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.arg("sort")
+        .write_stdin("release-1.10.0\nlatest\nrelease-1.9.0\n1.0.0\n");
+    cmd.assert()
+        .success()
+        .code(EXIT_CODE_OK)
+        .stdout("1.0.0\nrelease-1.9.0\nrelease-1.10.0\n")
+        .stderr(predicate::str::starts_with("skipped line 2: "));
+    Ok(())
+}
+
+#[test]
+fn satisfies_ok() -> Result<()> {
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.args(["satisfies", ">=1.4, <2", "1.3.0"]);
+    cmd.assert()
+        .failure()
+        .code(EXIT_CODE_MISMATCH)
+        .stdout("false\n");
+
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.args(["satisfies", "--max-satisfying", "^1.4"])
+        .write_stdin("v1.3.0\nv1.5.2\nv2.0.0\nv1.4.0\n");
+    cmd.assert().success().code(EXIT_CODE_OK).stdout("v1.5.2\n");
+    Ok(())
+}
+
+#[test]
+fn validate_ok() -> Result<()> {
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.args(["validate", "1.0.0-rc_1"]);
+    cmd.assert()
+        .failure()
+        .code(EXIT_CODE_MISMATCH)
+        .stdout("Unexpected character '_' after version at byte 8\n1.0.0-rc_1\n        ^\n");
+    Ok(())
+}
+
+#[test]
+fn output_json_ok() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("VERSION");
+    std::fs::write(&path, "1.0.0\n")?;
+
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.args(["patch", "-n", "--output", "json", "-f"])
+        .arg(&path);
+    cmd.assert()
+        .failure()
+        .code(EXIT_CODE_CHANGED)
+        .stdout(
+            "{\"build\":null,\"bumped_part\":\"patch\",\"major\":1,\"minor\":0,\"patch\":1,\"pre\":null,\"prefix\":\"\",\"previous\":\"1.0.0\",\"suffix\":null}\n",
+        )
+        .stderr(predicate::str::ends_with("-1.0.0\n+1.0.1\n"));
+    Ok(())
+}
+
+#[test]
+fn get_ok() -> Result<()> {
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.args(["get", "major"]).write_stdin("release-3.1.0\n");
+    cmd.assert().success().code(EXIT_CODE_OK).stdout("3\n");
+
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.args(["get", "micro", "1.0.0"]);
+    cmd.assert().failure().code(EXIT_CODE_USAGE);
+    Ok(())
+}
+
+#[test]
+fn lines_ok() -> Result<()> {
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.args(["major", "--lines", "--keep-going"])
+        .write_stdin("api-1.0.0\nweb-x\nv0.9.1\n");
+    cmd.assert()
+        .failure()
+        .code(EXIT_CODE_PARSE)
+        .stdout("api-2.0.0\nv1.0.0\n")
+        .stderr("line 2: Can't find semver format. value: web-x\n1 line(s) failed\n");
+    Ok(())
+}
+
+#[test]
+fn exit_code_ok() -> Result<()> {
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.args(["patch", "-f", "not_found"]);
+    cmd.assert()
+        .failure()
+        .code(EXIT_CODE_IO)
+        .stderr(predicate::str::starts_with("not_found: "));
+    Ok(())
+}
+
+#[test]
+fn config_missing_file_ng() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let config = tmp_dir.path().join(".bump.toml");
+    std::fs::write(&config, "[[file]]\npath = \"MISSING\"\n")?;
+
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.args(["patch", "-c"]).arg(&config);
+    cmd.assert()
+        .failure()
+        .code(EXIT_CODE_IO)
+        .stderr(predicate::str::contains("MISSING: ").count(1));
+    Ok(())
+}
+
+#[test]
+fn config_inconsistent_ng() -> Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let config = tmp_dir.path().join(".bump.toml");
+    std::fs::write(
+        &config,
+        "[[file]]\npath = \"A\"\n\n[[file]]\npath = \"B\"\n",
+    )?;
+    std::fs::write(tmp_dir.path().join("A"), "1.0.0\n")?;
+    std::fs::write(tmp_dir.path().join("B"), "1.0.1\n")?;
+
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.args(["patch", "-c"]).arg(&config);
+    cmd.assert()
+        .failure()
+        .code(EXIT_CODE_PARSE)
+        .stderr(predicate::str::contains("Inconsistent versions"));
+    Ok(())
+}
+
+#[test]
+fn suffix_ok() -> Result<()> {
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.args(["patch", "chart-1.2.3-linux-amd64"]);
+    cmd.assert()
+        .success()
+        .code(EXIT_CODE_OK)
+        .stdout("chart-1.2.4\n")
+        .stderr(predicate::str::contains(
+            "dropped the pre-release 'linux-amd64'",
+        ));
+
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.args(["patch", "--known-suffixes", "-linux-amd64"])
+        .arg("chart-1.2.3-linux-amd64");
+    cmd.assert()
+        .success()
+        .stdout("chart-1.2.4-linux-amd64\n")
+        .stderr("");
+    Ok(())
+}
+
+#[test]
+fn suffix_ng() -> Result<()> {
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.args(["patch", "1.0.0-rc.01"]);
+    cmd.assert().failure().code(EXIT_CODE_PARSE);
+
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.arg("patch").write_stdin("1.2.3\n1.2.4\n");
+    cmd.assert().failure().code(EXIT_CODE_PARSE);
+
+    let tmp_dir = tempfile::tempdir()?;
+    let path = tmp_dir.path().join("pyproject.toml");
+    let contents = "requires = [\"setuptools>=61.0.0\"]\nversion = \"1.2.3\"\n";
+    std::fs::write(&path, contents)?;
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.args(["patch", "-w", "-f"]).arg(&path);
+    cmd.assert().failure().code(EXIT_CODE_PARSE);
+    assert_eq!(contents, std::fs::read_to_string(&path)?);
+    Ok(())
+}
+
+#[test]
+fn overflow_ng() -> Result<()> {
+    let mut cmd = Command::cargo_bin("bump")?;
+    cmd.args(["patch", "1.0.18446744073709551615"]);
+    cmd.assert()
+        .failure()
+        .code(EXIT_CODE_OVERFLOW)
+        .stderr("Can't bump patch any further. value: 1.0.18446744073709551615\n");
+    Ok(())
+}